@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Cannot locate the config directory (neither `XDG_CONFIG_HOME` nor `HOME` is set).")]
+    ConfigDirNotFound,
+    #[error("Cannot read `{0}`.")]
+    Read(PathBuf, #[source] cpt_stdx::fs::Error),
+    #[error("Cannot parse `{0}`.")]
+    Parse(PathBuf, #[source] Box<toml::de::Error>),
+    #[error("Cannot write `{0}`.")]
+    Write(PathBuf, #[source] cpt_stdx::fs::Error),
+    #[error("Invalid machine factor in `{0}`: {1}")]
+    InvalidFactor(PathBuf, String),
+}
+
+/// Elapsed time of [`benchmark`] on the reference machine, to which the factor is relative.
+///
+/// It is the fastest of 3 runs of a release build on the development machine
+/// (x86-64 Linux, about 90ms), rounded to 100ms.
+/// That machine is regarded as fast as a typical judge, so a factor is only as accurate
+/// as this assumption; `cpt calibrate --factor` stores a factor known otherwise.
+pub(crate) const REFERENCE_MS: u64 = 100;
+
+/// Result of `cpt calibrate`, stored per machine rather than per project.
+///
+/// ```toml
+/// machine_factor = 1.25
+/// ```
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy)]
+pub(crate) struct Calibration {
+    /// Elapsed time of [`benchmark`] on this machine relative to [`REFERENCE_MS`].
+    ///
+    /// Greater than 1 on a machine slower than the reference one.
+    pub(crate) machine_factor: f64,
+}
+
+/// `$XDG_CONFIG_HOME/cpt/calibration.toml`, or `$HOME/.config/cpt/calibration.toml`.
+pub(crate) fn path() -> Option<PathBuf> {
+    let non_empty = |key| std::env::var_os(key).filter(|value| !value.is_empty());
+    non_empty("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("cpt").join("calibration.toml"))
+}
+
+/// Loads the calibration of this machine, or `None` if `cpt calibrate` has not been run.
+pub(crate) fn load() -> Result<Option<Calibration>, Error> {
+    let Some(path) = path().filter(|path| path.is_file()) else {
+        return Ok(None);
+    };
+    let content = cpt_stdx::fs::read(&path).map_err(|e| Error::Read(path.to_owned(), e))?;
+    let calibration: Calibration =
+        toml::from_str(&content).map_err(|e| Error::Parse(path.to_owned(), Box::new(e)))?;
+    crate::config::check_tl_scale(calibration.machine_factor)
+        .map_err(|e| Error::InvalidFactor(path.to_owned(), e))?;
+    log::debug!("[Calibration] Loaded `{}`", path.display());
+    Ok(Some(calibration))
+}
+
+/// Factor of this machine, from the calibration if `cpt calibrate` has been run.
+///
+/// The calibration is loaded on the first call. A broken one is warned and ignored.
+pub(crate) fn machine_factor() -> Option<f64> {
+    static MACHINE_FACTOR: std::sync::OnceLock<Option<f64>> = std::sync::OnceLock::new();
+    *MACHINE_FACTOR.get_or_init(|| match load() {
+        Ok(calibration) => calibration.map(|c| c.machine_factor),
+        Err(e) => {
+            log::warn!("[Calibration] {} Ignored.", e);
+            None
+        }
+    })
+}
+
+/// Stores `calibration`, and returns the path of the file.
+pub(crate) fn save(calibration: &Calibration) -> Result<PathBuf, Error> {
+    let path = path().ok_or(Error::ConfigDirNotFound)?;
+    let content = toml::to_string(calibration).expect("calibration is serializable");
+    cpt_stdx::fs::write(&path, content, true).map_err(|e| Error::Write(path.to_owned(), e))?;
+    Ok(path)
+}
+
+/// Runs a fixed single-threaded CPU workload (a sieve and a sort), and returns the elapsed time.
+///
+/// It is only comparable with [`REFERENCE_MS`] in a release build.
+pub(crate) fn benchmark() -> u64 {
+    let start = std::time::Instant::now();
+
+    const N: usize = 5_000_000;
+    let mut composite = vec![false; N + 1];
+    for i in 2..=N {
+        if !composite[i] && i * i <= N {
+            for j in (i * i..=N).step_by(i) {
+                composite[j] = true;
+            }
+        }
+    }
+    let primes = (2..=N).filter(|&i| !composite[i]).count();
+
+    let mut rng = cpt_stdx::random::Rng::new(1);
+    let mut values = (0..1_000_000).map(|_| rng.next_u64()).collect::<Vec<_>>();
+    values.sort_unstable();
+
+    std::hint::black_box((primes, values));
+    start.elapsed().as_millis() as u64
+}
@@ -1,5 +1,12 @@
+mod bench;
+mod calibrate;
+mod compare;
+mod gen;
+mod gen_out;
 mod hack;
+mod run;
 mod test;
+mod validate;
 
 #[derive(thiserror::Error, Debug)]
 pub(super) enum Error {
@@ -7,24 +14,72 @@ pub(super) enum Error {
     TestFailed(#[from] crate::commands::test::Error),
     #[error("Hack failed.")]
     HackFailed(#[from] crate::commands::hack::Error),
+    #[error("Run failed.")]
+    RunFailed(#[from] crate::commands::run::Error),
+    #[error("Validate failed.")]
+    ValidateFailed(#[from] crate::commands::validate::Error),
+    #[error("Calibrate failed.")]
+    CalibrateFailed(#[from] crate::commands::calibrate::Error),
+    #[error("Bench failed.")]
+    BenchFailed(#[from] crate::commands::bench::Error),
+    #[error("Compare failed.")]
+    CompareFailed(#[from] crate::commands::compare::Error),
+    #[error("Generation failed.")]
+    GenFailed(#[from] crate::commands::gen::Error),
+    #[error("Output generation failed.")]
+    GenOutFailed(#[from] crate::commands::gen_out::Error),
+    #[error("Cannot load config.")]
+    Config(#[from] crate::config::Error),
 }
 
 #[derive(clap::Subcommand, Debug)]
 pub(super) enum Command {
-    #[command(subcommand, visible_alias = "t")]
-    Test(crate::commands::test::Command),
-    #[command(subcommand, visible_alias = "h")]
-    Hack(crate::commands::hack::Command),
+    #[command(visible_alias = "t")]
+    Test(crate::commands::test::Args),
+    #[command(visible_alias = "h")]
+    Hack(crate::commands::hack::Args),
+    #[command(visible_alias = "r")]
+    Run(crate::commands::run::Args),
+    #[command(visible_alias = "v")]
+    Validate(crate::commands::validate::Args),
+    Bench(crate::commands::bench::Args),
+    Calibrate(crate::commands::calibrate::Args),
+    Compare(crate::commands::compare::Args),
+    Gen(crate::commands::gen::Args),
+    GenOut(crate::commands::gen_out::Args),
 }
 
 impl Command {
     pub(super) fn run(&self) -> Result<(), Error> {
+        // `cpt calibrate` does not use the config, so that a broken `cpt.toml` does not block it.
+        let config = crate::config::load;
         match self {
-            Command::Test(command) => {
-                command.run()?;
+            Command::Test(args) => {
+                args.run(&config()?)?;
             }
-            Command::Hack(command) => {
-                command.run()?;
+            Command::Hack(args) => {
+                args.run(&config()?)?;
+            }
+            Command::Run(args) => {
+                crate::commands::run::run(args, &config()?)?;
+            }
+            Command::Validate(args) => {
+                crate::commands::validate::run(args, &config()?)?;
+            }
+            Command::Bench(args) => {
+                crate::commands::bench::run(args, &config()?)?;
+            }
+            Command::Calibrate(args) => {
+                crate::commands::calibrate::run(args)?;
+            }
+            Command::Compare(args) => {
+                crate::commands::compare::run(args, &config()?)?;
+            }
+            Command::Gen(args) => {
+                crate::commands::gen::run(args, &config()?)?;
+            }
+            Command::GenOut(args) => {
+                crate::commands::gen_out::run(args, &config()?)?;
             }
         }
         Ok(())
@@ -0,0 +1,294 @@
+use std::path::PathBuf;
+
+use itertools::Itertools;
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = false, short = 'c')]
+    command: Option<String>,
+    /// Previous version of the program, compared with `-c` run by run
+    #[arg(required = false, long = "baseline")]
+    baseline: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
+    /// Number of runs per case
+    #[arg(long = "repeat", default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    repeat: u64,
+    /// Warn about the cases whose slowest run takes more than this percentage of the time limit
+    #[arg(long = "near-tle", default_value_t = 80)]
+    near_tle_percent: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("`{0}` is not found.")]
+    CasedirNotFound(PathBuf),
+    #[error("`{0}` is not a directory.")]
+    CasedirNotDir(PathBuf),
+    #[error("No case found in `{0}`.")]
+    CaseNotFound(PathBuf),
+    #[error("Cannot create a temporary directory.")]
+    TempDir(#[source] std::io::Error),
+    #[error("Judge failed.")]
+    JudgeFailed(#[from] crate::judge::batch::Error),
+}
+
+/// Minimum, median and maximum of repeated measurements.
+#[derive(Debug, Clone, Copy)]
+struct Stats {
+    min: u64,
+    median: u64,
+    max: u64,
+}
+impl Stats {
+    /// `None` if `values` is empty. The median of an even number of values is the lower one.
+    fn new(mut values: Vec<u64>) -> Option<Self> {
+        values.sort();
+        Some(Self {
+            min: *values.first()?,
+            median: values[(values.len() - 1) / 2],
+            max: *values.last()?,
+        })
+    }
+}
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}/{}ms", self.min, self.median, self.max)
+    }
+}
+
+/// Minimum number of paired runs to claim high confidence.
+const MIN_PAIRS: usize = 3;
+
+/// How reliable the comparison with the baseline is, judged from the paired runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Confidence {
+    /// Every pair agrees on which is faster: `true` if the program is.
+    High(bool),
+    /// The pairs disagree, so the difference may be noise.
+    Low,
+    /// Fewer than [`MIN_PAIRS`] pairs, given as the number of them.
+    Unavailable(usize),
+}
+impl Confidence {
+    /// Judges from the differences of the baseline from the program in the interleaved pairs.
+    fn new(differences: &[i64]) -> Self {
+        if differences.len() < MIN_PAIRS {
+            Confidence::Unavailable(differences.len())
+        } else if differences.iter().all(|&difference| difference > 0) {
+            Confidence::High(true)
+        } else if differences.iter().all(|&difference| difference < 0) {
+            Confidence::High(false)
+        } else {
+            Confidence::Low
+        }
+    }
+}
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Confidence::High(_) => write!(f, "high confidence"),
+            Confidence::Low => write!(f, "low confidence"),
+            Confidence::Unavailable(pairs) => write!(f, "confidence n/a, {} paired run(s)", pairs),
+        }
+    }
+}
+
+/// Counts of the non-AC verdicts, e.g. `WA:1,TLE:2`.
+fn describe_failures(failures: &[usize]) -> String {
+    use strum::IntoEnumIterator;
+
+    crate::judge::Verdict::iter()
+        .filter(|verdict| failures[*verdict as usize] > 0)
+        .map(|verdict| format!("{}:{}", verdict, failures[verdict as usize]))
+        .join(",")
+}
+
+/// Runs the program on every case `--repeat` times, and reports the timing statistics.
+///
+/// Only the AC runs are measured, and the others are reported per case.
+///
+/// With `--baseline`, the runs of both programs are interleaved, alternating which runs first,
+/// so that the drift of the machine affects both equally.
+pub(super) fn run(args: &Args, config: &crate::config::Problem) -> Result<(), Error> {
+    use strum::EnumCount;
+
+    use crate::judge::batch::judge;
+    use crate::judge::Verdict;
+
+    log::info!("[Bench] Start");
+    let command = args
+        .command
+        .as_ref()
+        .or(config.command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-c"))?;
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
+    if !dir.exists() {
+        return Err(Error::CasedirNotFound(dir.to_owned()));
+    }
+    if !dir.is_dir() {
+        return Err(Error::CasedirNotDir(dir.to_owned()));
+    }
+
+    let mut cases = crate::testcase::collect(dir);
+    if cases.is_empty() {
+        return Err(Error::CaseNotFound(dir.to_owned()));
+    }
+    cases.sort_by(|a, b| a.casename.cmp(&b.casename));
+
+    let limits = config.limits(
+        None,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    // The outputs are written here, not into the testcase directory.
+    let tempdir = cpt_stdx::tempfile::tempdir().map_err(Error::TempDir)?;
+    let temp_dir = tempdir.path();
+    let mut slowest: Option<(String, u64)> = None;
+    let (mut near_tle, mut failed) = (0, 0);
+    let (mut faster, mut slower, mut unclear) = (0, 0, 0);
+    for case in cases {
+        let baseline_case = crate::testcase::Testcase {
+            casename: case.casename.to_owned() + ".baseline",
+            ..case.to_owned()
+        };
+        let (mut wall, mut cpu, mut baseline_wall) = (Vec::new(), Vec::new(), Vec::new());
+        // Elapsed time of the baseline minus the program, in the runs where both are AC.
+        let mut differences = Vec::new();
+        let (mut failures, mut baseline_failures) = ([0; Verdict::COUNT], [0; Verdict::COUNT]);
+        for run in 0..args.repeat {
+            let mut run_baseline = || -> Result<Option<u64>, Error> {
+                let Some(baseline) = &args.baseline else {
+                    return Ok(None);
+                };
+                let judgement = judge(baseline, baseline_case.to_owned(), &limits, temp_dir)?;
+                Ok(match judgement.verdict {
+                    Verdict::Ac => {
+                        baseline_wall.push(judgement.elapsed_ms);
+                        Some(judgement.elapsed_ms)
+                    }
+                    verdict => {
+                        baseline_failures[verdict as usize] += 1;
+                        None
+                    }
+                })
+            };
+            let mut baseline_elapsed = None;
+            if run % 2 == 0 {
+                baseline_elapsed = run_baseline()?;
+            }
+            let judgement = judge(command, case.to_owned(), &limits, temp_dir)?;
+            let elapsed = match judgement.verdict {
+                Verdict::Ac => {
+                    wall.push(judgement.elapsed_ms);
+                    cpu.extend(judgement.cpu_ms);
+                    Some(judgement.elapsed_ms)
+                }
+                verdict => {
+                    failures[verdict as usize] += 1;
+                    None
+                }
+            };
+            if run % 2 == 1 {
+                baseline_elapsed = run_baseline()?;
+            }
+            if let (Some(baseline_elapsed), Some(elapsed)) = (baseline_elapsed, elapsed) {
+                differences.push(baseline_elapsed as i64 - elapsed as i64);
+            }
+        }
+        if failures.iter().any(|&count| count > 0) {
+            log::warn!(
+                "[Bench][{}] Excluded non-AC run(s) ({})",
+                case.casename,
+                describe_failures(&failures)
+            );
+            failed += 1;
+        }
+        if baseline_failures.iter().any(|&count| count > 0) {
+            log::warn!(
+                "[Bench][{}] Excluded non-AC baseline run(s) ({})",
+                case.casename,
+                describe_failures(&baseline_failures)
+            );
+        }
+        let Some(wall) = Stats::new(wall) else {
+            continue;
+        };
+        let percent = wall.max * 100 / limits.time_ms.max(1);
+        log::info!(
+            "[Bench][{}] Wall: {} CPU: {} (min/median/max) {}% of {}ms",
+            case.casename,
+            wall,
+            Stats::new(cpu).map_or("-".to_owned(), |cpu| cpu.to_string()),
+            percent,
+            limits.time_ms
+        );
+        if percent >= args.near_tle_percent {
+            log::warn!(
+                "[Bench][{}] Near TLE ({}ms/{}ms)",
+                case.casename,
+                wall.max,
+                limits.time_ms
+            );
+            near_tle += 1;
+        }
+        if let Some(baseline_wall) = Stats::new(baseline_wall) {
+            let speedup = baseline_wall.median.max(1) as f64 / wall.median.max(1) as f64;
+            let confidence = Confidence::new(&differences);
+            log::info!(
+                "[Bench][{}] Baseline Wall: {} Speedup: {:.2}x ({})",
+                case.casename,
+                baseline_wall,
+                speedup,
+                confidence
+            );
+            match confidence {
+                Confidence::Low | Confidence::Unavailable(_) => unclear += 1,
+                Confidence::High(true) => faster += 1,
+                Confidence::High(false) => {
+                    log::warn!(
+                        "[Bench][{}] Regression: {}ms -> {}ms",
+                        case.casename,
+                        baseline_wall.median,
+                        wall.median
+                    );
+                    slower += 1;
+                }
+            }
+        }
+        if slowest
+            .as_ref()
+            .is_none_or(|(_, elapsed_ms)| wall.max > *elapsed_ms)
+        {
+            slowest = Some((case.casename, wall.max));
+        }
+    }
+
+    if let Some((casename, elapsed_ms)) = slowest {
+        log::info!("[Bench] Slowest: {} ({}ms)", casename, elapsed_ms);
+    }
+    if args.baseline.is_some() {
+        log::info!(
+            "[Bench] Baseline (Faster:{},Slower:{},Unclear:{})",
+            faster,
+            slower,
+            unclear
+        );
+    }
+    log::info!("[Bench] End (Near TLE:{},Failed:{})", near_tle, failed);
+    Ok(())
+}
@@ -0,0 +1,59 @@
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    /// Stores this factor instead of measuring it
+    #[arg(required = false, long = "factor")]
+    factor: Option<f64>,
+    /// Number of benchmark runs, of which the fastest is taken
+    #[arg(long = "repeat", default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..))]
+    repeat: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("The machine factor must be positive, but `{0}` is given.")]
+    InvalidFactor(f64),
+    #[error("Cannot measure the machine factor with a debug build of cpt. Use a release build, or `--factor`.")]
+    DebugBuild,
+    #[error("Cannot save the calibration.")]
+    Calibration(#[from] crate::calibration::Error),
+}
+
+/// Measures how fast this machine is compared to the reference one, and stores the factor
+/// by which the time limits are scaled.
+pub(super) fn run(args: &Args) -> Result<(), Error> {
+    use crate::calibration::{benchmark, save, Calibration, REFERENCE_MS};
+
+    log::info!("[Calibrate] Start");
+    let machine_factor = match args.factor {
+        Some(factor) => factor,
+        // A debug build runs the benchmark many times slower than the reference release build.
+        None if cfg!(debug_assertions) => return Err(Error::DebugBuild),
+        None => {
+            let elapsed_ms = (1..=args.repeat)
+                .map(|run| {
+                    let elapsed_ms = benchmark();
+                    log::info!("[Calibrate] Run {}: {}ms", run, elapsed_ms);
+                    elapsed_ms
+                })
+                .min()
+                .unwrap();
+            log::info!(
+                "[Calibrate] Fastest: {}ms (Reference: {}ms)",
+                elapsed_ms,
+                REFERENCE_MS
+            );
+            // Rounded, as the measurement is not more precise than that.
+            (elapsed_ms.max(1) as f64 / REFERENCE_MS as f64 * 100.0).round() / 100.0
+        }
+    };
+    if !(machine_factor.is_finite() && machine_factor > 0.0) {
+        return Err(Error::InvalidFactor(machine_factor));
+    }
+    let path = save(&Calibration { machine_factor })?;
+    log::info!(
+        "[Calibrate] Machine factor: {:.2} (Saved to `{}`)",
+        machine_factor,
+        path.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = true, short = 'c')]
+    commands: Vec<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("At least 2 solutions are required, but {0} given.")]
+    TooFewSolutions(usize),
+    #[error("`{0}` is not found.")]
+    CasedirNotFound(PathBuf),
+    #[error("`{0}` is not a directory.")]
+    CasedirNotDir(PathBuf),
+    #[error("No case found in `{0}`.")]
+    CaseNotFound(PathBuf),
+    #[error("Comparison failed.")]
+    CompareFailed(#[from] crate::compare::Error),
+}
+
+/// Runs all the solutions on every case, and reports the cases where their outputs differ.
+pub(super) fn run(args: &Args, config: &crate::config::Problem) -> Result<(), Error> {
+    log::info!("[Compare] Start");
+    if args.commands.len() < 2 {
+        return Err(Error::TooFewSolutions(args.commands.len()));
+    }
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
+    if !dir.exists() {
+        return Err(Error::CasedirNotFound(dir.to_owned()));
+    }
+    if !dir.is_dir() {
+        return Err(Error::CasedirNotDir(dir.to_owned()));
+    }
+
+    let cases = crate::testcase::collect(dir);
+    if cases.is_empty() {
+        return Err(Error::CaseNotFound(dir.to_owned()));
+    }
+
+    for (i, command) in args.commands.iter().enumerate() {
+        log::info!("[Compare] #{}: {}", i + 1, command);
+    }
+    let limits = config.limits(
+        None,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    let mut disagreements = Vec::new();
+    for case in &cases {
+        if !crate::compare::compare(&args.commands, case, &limits, dir)?.agrees() {
+            disagreements.push(case.casename.to_owned());
+        }
+    }
+    log::info!(
+        "[Compare] End (Agree:{},Disagree:{})",
+        cases.len() - disagreements.len(),
+        disagreements.len()
+    );
+    if !disagreements.is_empty() {
+        log::warn!("[Compare] Disagreed: {}", disagreements.join(", "));
+    }
+    Ok(())
+}
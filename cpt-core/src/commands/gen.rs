@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+/// How each case was made, one `<name>\t<seed>\t<command>\t<validity>` per line.
+const MANIFEST_FILENAME: &str = "manifest.tsv";
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = false, short = 'i')]
+    input_generator: Option<String>,
+    #[arg(required = false, short = 'o')]
+    output_generator: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
+    /// Number of cases generated per batch
+    #[arg(short = 'n', default_value_t = 1)]
+    count: usize,
+    /// Seed of the first case, incremented for each following case
+    #[arg(long = "seed", default_value_t = 1)]
+    seed: u64,
+    /// Generator arguments of a batch (repeatable), passed before the seed
+    #[arg(required = false, long = "batch")]
+    batches: Vec<String>,
+    #[arg(required = false, long = "validator")]
+    validator: Option<String>,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+    /// Overwrite the existing cases (a case is replaced only once it is generated)
+    #[arg(long = "force")]
+    force: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("`{0}` is not a directory.")]
+    CasedirNotDir(PathBuf),
+    #[error("`{0}` already exists. Use `--force` to overwrite.")]
+    CaseExists(PathBuf),
+    #[error("Cannot create `{0}`.")]
+    CreateDir(PathBuf),
+    #[error("Cannot remove the stale output `{0}`.")]
+    RemoveOutput(PathBuf),
+    #[error("Generation failed.")]
+    GenerationFailed(#[from] crate::generator::Error),
+    #[error("Validation failed.")]
+    ValidationFailed(#[from] crate::validator::Error),
+    #[error("Cannot write the manifest.")]
+    Manifest(#[source] cpt_stdx::fs::Error),
+    #[error("{0} invalid input(s) generated.")]
+    InvalidInput(usize),
+}
+
+/// Generates `-n` numbered cases per batch into the testcase directory.
+///
+/// The `k`-th case is generated with the batch arguments followed by the seed `--seed + k`.
+pub(super) fn run(args: &Args, config: &crate::config::Problem) -> Result<(), Error> {
+    use cpt_stdx::fs;
+
+    use crate::generator::generate;
+    use crate::testcase::Testcase;
+
+    log::info!("[Gen] Start");
+    let input_generator = args
+        .input_generator
+        .as_ref()
+        .or(config.input_generator.as_ref())
+        .ok_or(Error::OptionNotSpecified("-i"))?;
+    let output_generator = args
+        .output_generator
+        .to_owned()
+        .or(config.output_generator.to_owned());
+    let validator = args.validator.as_ref().or(config.validator.as_ref());
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
+    if dir.exists() && !dir.is_dir() {
+        return Err(Error::CasedirNotDir(dir.to_owned()));
+    }
+    std::fs::create_dir_all(dir).map_err(|_| Error::CreateDir(dir.to_owned()))?;
+
+    let batches = if args.batches.is_empty() {
+        vec![String::new()]
+    } else {
+        args.batches.to_owned()
+    };
+    let total = batches.len() * args.count;
+    let width = total.to_string().len().max(2);
+    let timelimit = config.limits(None, args.timelimit_ms, None, None).time_ms * 10;
+
+    let casename = |no: usize| format!("{:0width$}", no);
+    if !args.force {
+        if let Some(input) = (1..=total)
+            .map(|no| dir.join(casename(no) + ".in"))
+            .find(|input| input.exists())
+        {
+            return Err(Error::CaseExists(input));
+        }
+    }
+
+    let mut manifest = String::new();
+    let mut invalid = 0;
+    let mut no = 0;
+    for batch in &batches {
+        for _ in 0..args.count {
+            no += 1;
+            let casename = casename(no);
+            let case = Testcase {
+                input: dir.join(casename.to_owned() + ".in"),
+                output: Some(dir.join(casename.to_owned() + ".out")),
+                casename,
+            };
+            // An output left by a previous generation does not match the new input.
+            if let Some(output) = case.output.as_ref().filter(|_| output_generator.is_none()) {
+                if output.exists() {
+                    std::fs::remove_file(output)
+                        .map_err(|_| Error::RemoveOutput(output.to_owned()))?;
+                    log::info!("[Gen][{}] Removed the stale output", case.casename);
+                }
+            }
+            let seed = args.seed + no as u64 - 1;
+            let generator_args = batch
+                .split_whitespace()
+                .map(str::to_owned)
+                .chain([seed.to_string()])
+                .collect::<Vec<_>>();
+            generate(
+                &case,
+                input_generator,
+                &generator_args,
+                &output_generator,
+                timelimit,
+            )?;
+            let validity = match validator {
+                Some(validator) => {
+                    match crate::validator::validate(validator, &case.input, timelimit)? {
+                        Some(reason) => {
+                            log::warn!("[Gen][{}] Invalid input: {}", case.casename, reason);
+                            invalid += 1;
+                            "invalid"
+                        }
+                        None => "valid",
+                    }
+                }
+                None => "unchecked",
+            };
+            manifest += &format!(
+                "{}\t{}\t{} {}\t{}\n",
+                case.casename,
+                seed,
+                input_generator,
+                generator_args.join(" "),
+                validity
+            );
+            // Written per case, so that it covers the cases generated before a failure.
+            fs::write(dir.join(MANIFEST_FILENAME), &manifest, true).map_err(Error::Manifest)?;
+        }
+    }
+    log::info!("[Gen] End (Generated:{},Invalid:{})", total, invalid);
+    if invalid > 0 {
+        return Err(Error::InvalidInput(invalid));
+    }
+    Ok(())
+}
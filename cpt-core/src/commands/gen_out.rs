@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = false, short = 'o')]
+    output_generator: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+    #[arg(long = "force")]
+    force: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("`{0}` is not found.")]
+    CasedirNotFound(PathBuf),
+    #[error("`{0}` is not a directory.")]
+    CasedirNotDir(PathBuf),
+    #[error("No case found in `{0}`.")]
+    CaseNotFound(PathBuf),
+    #[error("Generation failed.")]
+    GenerationFailed(#[from] crate::generator::Error),
+}
+
+/// Writes the expected output of every input without one, by running the reference solution.
+///
+/// Existing outputs are kept unless `--force` is given.
+pub(super) fn run(args: &Args, config: &crate::config::Problem) -> Result<(), Error> {
+    use crate::generator::generate_output;
+
+    log::info!("[Gen Out] Start");
+    let output_generator = args
+        .output_generator
+        .to_owned()
+        .or(config.output_generator.to_owned())
+        .ok_or(Error::OptionNotSpecified("-o"))?;
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
+    if !dir.exists() {
+        return Err(Error::CasedirNotFound(dir.to_owned()));
+    }
+    if !dir.is_dir() {
+        return Err(Error::CasedirNotDir(dir.to_owned()));
+    }
+
+    let cases = crate::testcase::collect(dir);
+    if cases.is_empty() {
+        return Err(Error::CaseNotFound(dir.to_owned()));
+    }
+
+    let limits = config.limits(None, args.timelimit_ms, None, None);
+    let timelimit = limits.time_ms * 10;
+    let (mut created, mut overwritten, mut skipped) = (0, 0, 0);
+    for mut case in cases {
+        if case.output.is_some() && !args.force {
+            log::info!("[Gen Out][{}] Skipped (already exists)", case.casename);
+            skipped += 1;
+            continue;
+        }
+        let exists = case.output.is_some();
+        let output = case.output_path();
+        case.output = Some(output.to_owned());
+        generate_output(&case, &Some(output_generator.to_owned()), timelimit)?;
+        if exists {
+            log::info!(
+                "[Gen Out][{}] Overwritten `{}`",
+                case.casename,
+                output.display()
+            );
+            overwritten += 1;
+        } else {
+            log::info!(
+                "[Gen Out][{}] Created `{}`",
+                case.casename,
+                output.display()
+            );
+            created += 1;
+        }
+    }
+    log::info!(
+        "[Gen Out] End (Created:{},Overwritten:{},Skipped:{})",
+        created,
+        overwritten,
+        skipped
+    );
+    if skipped > 0 {
+        log::info!("[Gen Out] Use `--force` to overwrite existing outputs");
+    }
+    Ok(())
+}
@@ -1,7 +1,11 @@
 pub mod batch;
+pub mod compare;
 pub mod reactive;
+pub mod replay;
 pub mod special;
 
+use std::path::Path;
+
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
     #[error("Batch Hack failed.")]
@@ -10,6 +14,253 @@ pub(crate) enum Error {
     Special(#[from] crate::commands::hack::special::Error),
     #[error("Reactive Hack failed.")]
     Reactive(#[from] crate::commands::hack::reactive::Error),
+    #[error("Compare Hack failed.")]
+    Compare(#[from] crate::commands::hack::compare::Error),
+    #[error("Replay failed.")]
+    Replay(#[from] crate::commands::hack::replay::Error),
+    #[error("Cannot build the source.")]
+    Compile(#[from] crate::language::Error),
+    #[error("Compile error.")]
+    Compilation,
+}
+
+/// Options selecting the generated case to keep, shared by the hack variants.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct SearchArgs {
+    /// Keep only the case of this verdict, instead of any non-AC one
+    #[arg(required = false, long = "target")]
+    target: Option<crate::judge::Verdict>,
+    /// Run this number of trials, and keep the case with the longest elapsed time
+    #[arg(required = false, long = "slowest", conflicts_with = "target")]
+    slowest: Option<usize>,
+    /// Stop after this number of trials, even if no case is found
+    #[arg(required = false, long = "trials", conflicts_with = "slowest")]
+    trials: Option<usize>,
+    /// Save every generated input with its verdict to this directory, for `cpt hack replay`
+    #[arg(
+        required = false,
+        long = "corpus",
+        value_hint(clap::ValueHint::DirPath)
+    )]
+    corpus: Option<std::path::PathBuf>,
+}
+impl SearchArgs {
+    /// Starts the search, whose state is kept across the trials.
+    pub(crate) fn start(&self) -> Search<'_> {
+        Search {
+            args: self,
+            slowest: None,
+        }
+    }
+}
+
+/// What to do with a judged case.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Decision {
+    /// Tries the next input.
+    Next,
+    /// Saves the case, which is the slowest so far, and tries the next input.
+    SaveSlowest,
+    /// Saves the case, which is the one searched for, and stops.
+    Found,
+}
+
+/// State of the search over the trials.
+#[derive(Debug)]
+pub(crate) struct Search<'a> {
+    args: &'a SearchArgs,
+    /// Trial and elapsed time of the slowest case so far.
+    slowest: Option<(usize, u64)>,
+}
+impl Search<'_> {
+    /// Whether the `trial`-th trial (1-indexed) is run.
+    pub(crate) fn has_trial(&self, trial: usize) -> bool {
+        self.args
+            .slowest
+            .or(self.args.trials)
+            .is_none_or(|trials| trial <= trials)
+    }
+
+    /// Records the judged `case` of the `trial`-th trial to the corpus if enabled,
+    /// and decides what to do with it.
+    pub(crate) fn judged(
+        &mut self,
+        trial: usize,
+        case: &crate::testcase::Testcase,
+        judgement: &crate::judge::Judgement,
+    ) -> Result<Decision, crate::corpus::Error> {
+        if let Some(corpus) = &self.args.corpus {
+            crate::corpus::record(corpus, case, judgement)?;
+        }
+        if self.args.slowest.is_some() {
+            if self
+                .slowest
+                .is_none_or(|(_, elapsed_ms)| judgement.elapsed_ms > elapsed_ms)
+            {
+                self.slowest = Some((trial, judgement.elapsed_ms));
+                return Ok(Decision::SaveSlowest);
+            }
+            return Ok(Decision::Next);
+        }
+        let is_target = match self.args.target {
+            Some(target) => judgement.verdict == target,
+            None => !judgement.verdict.is_ac(),
+        };
+        Ok(if is_target {
+            Decision::Found
+        } else {
+            Decision::Next
+        })
+    }
+
+    /// Logs the slowest case if searched, or that the trials ran out without `found`.
+    pub(crate) fn finish(&self, tag: &str, found: bool) {
+        if let Some((trial, elapsed_ms)) = self.slowest {
+            log::info!("[{}] Slowest: {}ms (Trial {})", tag, elapsed_ms, trial);
+        } else if let (Some(trials), false) = (self.args.trials, found) {
+            log::warn!("[{}] Not found in {} trial(s)", tag, trials);
+        }
+    }
+}
+
+/// A hack variant, whose trials are run by [`hunt`].
+pub(crate) trait Hunter {
+    type Error: From<crate::validator::Error>
+        + From<crate::corpus::Error>
+        + From<crate::testcase::Error>
+        + From<std::io::Error>;
+
+    /// Tag of the logs (e.g. `Batch Hack`).
+    const TAG: &'static str;
+
+    /// Parameters of the `trial`-th trial, saved with the case.
+    fn params(&self, trial: usize) -> String;
+
+    /// Generates the input of the `trial`-th trial as `temp_case`.
+    fn generate(
+        &self,
+        trial: usize,
+        temp_case: &crate::testcase::Testcase,
+    ) -> Result<crate::testcase::Testcase, Self::Error>;
+
+    /// Judges `case`, leaving the intermediate files in `temp_dir`.
+    fn judge(
+        &self,
+        case: &crate::testcase::Testcase,
+        temp_dir: &Path,
+    ) -> Result<crate::judge::Judgement, Self::Error>;
+
+    /// Result of a trial in the log.
+    fn describe(&self, judgement: &crate::judge::Judgement) -> String {
+        format!("{} ({}ms)", judgement.verdict, judgement.elapsed_ms)
+    }
+
+    /// Called with the case searched for before it is saved (e.g. to shrink it).
+    fn found(
+        &self,
+        _case: &crate::testcase::Testcase,
+        _judgement: &crate::judge::Judgement,
+        _temp_dir: &Path,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called after `case` is saved as `saved` in the testcase directory.
+    fn saved(
+        &self,
+        _case: &crate::testcase::Testcase,
+        _saved: &crate::testcase::Testcase,
+        _temp_dir: &Path,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Runs the trials of `hunter` until the search ends:
+/// generates an input, validates it, judges it, then saves the case if decided so.
+pub(crate) fn hunt<H: Hunter>(
+    hunter: &H,
+    search: &SearchArgs,
+    dir: &Path,
+    validator: Option<&String>,
+    timelimit_generator: u64,
+) -> Result<(), H::Error> {
+    let tag = H::TAG;
+    let mut search = search.start();
+    // Private working directory of this session, removed on exit.
+    cpt_stdx::tempfile::remove_stale();
+    let tempdir = cpt_stdx::tempfile::tempdir()?;
+    let temp_dir = tempdir.path();
+    let temp_case = crate::testcase::new_hackcase(temp_dir);
+    let mut hackcase = crate::testcase::Hackcase::new(dir);
+    let mut found = false;
+    let mut trial = 0;
+    loop {
+        trial += 1;
+        if !search.has_trial(trial) {
+            break;
+        }
+        log::info!("[{}][Trial {}] Start", tag, trial);
+        let params = hunter.params(trial);
+        if !params.is_empty() {
+            log::info!("[{}][Trial {}] Params: {}", tag, trial, params);
+        }
+        let case = hunter.generate(trial, &temp_case)?;
+        if let Some(validator) = validator {
+            if let Some(reason) =
+                crate::validator::validate(validator, &case.input, timelimit_generator)?
+            {
+                log::warn!("[{}][Trial {}] Invalid input: {}", tag, trial, reason);
+                continue;
+            }
+        }
+        let judgement = hunter.judge(&case, temp_dir)?;
+        log::info!(
+            "[{}][Trial {}] End: {}",
+            tag,
+            trial,
+            hunter.describe(&judgement)
+        );
+        match search.judged(trial, &case, &judgement)? {
+            Decision::Next => continue,
+            Decision::SaveSlowest => {}
+            Decision::Found => {
+                hunter.found(&case, &judgement, temp_dir)?;
+                found = true;
+            }
+        }
+        let saved = hackcase.save(&case, temp_dir, &params)?;
+        hunter.saved(&case, saved, temp_dir)?;
+        if found {
+            break;
+        }
+    }
+    search.finish(tag, found);
+    Ok(())
+}
+
+/// Without the subcommand, the variant is selected by `judge` in `cpt.toml`.
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(
+        required = false,
+        short = 's',
+        global = true,
+        value_hint(clap::ValueHint::FilePath)
+    )]
+    source: Option<std::path::PathBuf>,
+    #[arg(required = false, long = "validator", global = true)]
+    validator: Option<String>,
+    /// Factor applied to the time limit, on top of the per-language and machine factors
+    #[arg(
+        required = false,
+        long = "tl-scale",
+        global = true,
+        value_parser = crate::config::parse_tl_scale
+    )]
+    tl_scale: Option<f64>,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -20,20 +271,66 @@ pub(crate) enum Command {
     Special(crate::commands::hack::special::Args),
     #[command(visible_alias = "r")]
     Reactive(crate::commands::hack::reactive::Args),
+    #[command(visible_alias = "c")]
+    Compare(crate::commands::hack::compare::Args),
+    #[command(visible_alias = "rp")]
+    Replay(crate::commands::hack::replay::Args),
 }
 impl Command {
-    pub(crate) fn run(&self) -> Result<(), Error> {
-        use crate::commands::hack::{batch, reactive, special};
+    /// Solution command given by `-c`.
+    ///
+    /// `compare` takes its own commands, so the solution is never used.
+    fn command(&self) -> Option<&String> {
         match self {
-            Command::Batch(args) => {
-                batch::run(args)?;
+            Command::Batch(args) => args.command.as_ref(),
+            Command::Special(args) => args.command.as_ref(),
+            Command::Reactive(args) => args.command.as_ref(),
+            Command::Compare(args) => args.commands.first(),
+            Command::Replay(args) => args.command.as_ref(),
+        }
+    }
+}
+impl Args {
+    pub(crate) fn run(&self, config: &crate::config::Problem) -> Result<(), Error> {
+        use crate::commands::hack::{batch, compare, reactive, replay, special};
+        use crate::config::JudgeKind;
+
+        let compiled = config.compile(
+            self.source.as_ref(),
+            self.command.as_ref().and_then(Command::command),
+        )?;
+        let mut config = config.clone();
+        if compiled.error {
+            return Err(Error::Compilation);
+        }
+        if self.validator.is_some() {
+            config.validator = self.validator.to_owned();
+        }
+        if self.tl_scale.is_some() {
+            config.tl_scale = self.tl_scale;
+        }
+        let (config, compiled) = (&config, &compiled);
+        match &self.command {
+            Some(Command::Batch(args)) => {
+                batch::run(args, config, compiled)?;
+            }
+            Some(Command::Special(args)) => {
+                special::run(args, config, compiled)?;
+            }
+            Some(Command::Reactive(args)) => {
+                reactive::run(args, config, compiled)?;
             }
-            Command::Special(args) => {
-                special::run(args)?;
+            Some(Command::Compare(args)) => {
+                compare::run(args, config, compiled)?;
             }
-            Command::Reactive(args) => {
-                reactive::run(args)?;
+            Some(Command::Replay(args)) => {
+                replay::run(args, config, compiled)?;
             }
+            None => match config.judge.unwrap_or_default() {
+                JudgeKind::Batch => batch::run(&batch::Args::default(), config, compiled)?,
+                JudgeKind::Special => special::run(&special::Args::default(), config, compiled)?,
+                JudgeKind::Reactive => reactive::run(&reactive::Args::default(), config, compiled)?,
+            },
         }
         Ok(())
     }
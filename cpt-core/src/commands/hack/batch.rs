@@ -1,39 +1,229 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Default)]
 pub(crate) struct Args {
-    #[arg(required = true, short = 'c')]
-    command: String,
-    #[arg(required = true, short = 'i')]
-    input_generator: String,
+    #[arg(required = false, short = 'c')]
+    pub(super) command: Option<String>,
+    #[arg(required = false, short = 'i')]
+    input_generator: Option<String>,
+    #[arg(
+        required = false,
+        long = "spec",
+        conflicts_with = "input_generator",
+        value_hint(clap::ValueHint::FilePath)
+    )]
+    spec: Option<PathBuf>,
+    /// Seed of the first trial with `--spec`, increased by one on each trial
+    #[arg(long = "seed", default_value_t = 1)]
+    seed: u64,
     #[arg(required = false, short = 'o')]
     output_generator: Option<String>,
-    #[arg(required = true, short = 'd', value_hint(clap::ValueHint::FilePath))]
-    directory: PathBuf,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
     #[arg(required = false, short = 't')]
     timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
+    /// Parameter schedule of the input generator (e.g. `n=1..10,100`), passed as `-<name> <value>`
+    ///
+    /// The k-th trial takes the k-th value, and the last one after the schedule runs out.
+    /// Without the name (e.g. `1..10`), only the value is passed.
+    #[arg(required = false, long = "param")]
+    params: Vec<crate::generator::Param>,
+    #[command(flatten)]
+    search: crate::commands::hack::SearchArgs,
+    /// Shrink a failing case before saving it
+    #[arg(
+        long = "shrink",
+        default_value_if("shrinker", clap::builder::ArgPredicate::IsPresent, "true")
+    )]
+    shrink: bool,
+    /// Command that reads the current input and writes a smaller candidate, in place of the
+    /// built-in shrinking (implies `--shrink`)
+    #[arg(required = false, long = "shrinker")]
+    shrinker: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
     #[error("`{0}` is not found.")]
     CasedirNotFound(PathBuf),
     #[error("`{0}` is not a directory.")]
     CasedirNotDir(PathBuf),
     #[error("Generation failed.")]
     GenerationFailed(#[from] crate::generator::Error),
+    #[error("Invalid spec.")]
+    SpecFailed(#[from] crate::spec::Error),
     #[error("Judge failed.")]
     JudgeFailed(#[from] crate::judge::batch::Error),
+    #[error("Validation failed.")]
+    ValidationFailed(#[from] crate::validator::Error),
+    #[error("Failed to save to the corpus.")]
+    CorpusFailed(#[from] crate::corpus::Error),
+    #[error("Cannot create a temporary directory.")]
+    TempDir(#[from] std::io::Error),
     #[error("Failed to copy testcase.")]
     TestcaseCopy(#[from] crate::testcase::Error),
+    #[error("Shrinking failed.")]
+    ShrinkFailed(#[from] crate::shrink::Error),
+    #[error("Cannot access the generated case.")]
+    HackcaseAccess(#[from] cpt_stdx::fs::Error),
 }
 
-pub(super) fn run(args: &Args) -> Result<(), Error> {
-    use crate::generator::generate;
-    use crate::judge::batch::judge;
+/// Where the input of each trial comes from.
+enum Source<'a> {
+    /// The built-in generator from `--spec`.
+    Spec(crate::spec::Spec),
+    /// The input generator command.
+    Command(&'a str),
+}
+
+struct Hunter<'a> {
+    args: &'a Args,
+    config: &'a crate::config::Problem,
+    command: &'a str,
+    source: Source<'a>,
+    output_generator: Option<String>,
+    limits: crate::judge::Limits,
+    timelimit_generator: u64,
+    reducer: Option<crate::shrink::Reducer<'a>>,
+}
+impl crate::commands::hack::Hunter for Hunter<'_> {
+    type Error = Error;
+
+    const TAG: &'static str = "Batch Hack";
+
+    fn params(&self, trial: usize) -> String {
+        use crate::generator::describe_params;
+
+        match self.source {
+            Source::Spec(_) => format!(
+                "{} seed={}",
+                describe_params(&self.args.params, trial),
+                self.args.seed + trial as u64 - 1
+            )
+            .trim_start()
+            .to_owned(),
+            Source::Command(_) => describe_params(&self.args.params, trial),
+        }
+    }
+
+    fn generate(
+        &self,
+        trial: usize,
+        temp_case: &crate::testcase::Testcase,
+    ) -> Result<crate::testcase::Testcase, Error> {
+        use crate::generator::{generate, generate_output, param_args, param_values};
+
+        Ok(match &self.source {
+            Source::Spec(spec) => {
+                let input = spec.generate(
+                    self.args.seed + trial as u64 - 1,
+                    &param_values(&self.args.params, trial),
+                )?;
+                cpt_stdx::fs::write(&temp_case.input, input, true)?;
+                generate_output(temp_case, &self.output_generator, self.timelimit_generator)?
+            }
+            Source::Command(input_generator) => generate(
+                temp_case,
+                input_generator,
+                &param_args(&self.args.params, trial),
+                &self.output_generator,
+                self.timelimit_generator,
+            )?,
+        })
+    }
+
+    fn judge(
+        &self,
+        case: &crate::testcase::Testcase,
+        temp_dir: &Path,
+    ) -> Result<crate::judge::Judgement, Error> {
+        Ok(crate::judge::batch::judge(
+            self.command,
+            case.to_owned(),
+            &self.limits,
+            temp_dir,
+        )?)
+    }
+
+    fn found(
+        &self,
+        case: &crate::testcase::Testcase,
+        judgement: &crate::judge::Judgement,
+        temp_dir: &Path,
+    ) -> Result<(), Error> {
+        use cpt_stdx::fs;
+
+        use crate::generator::generate_output;
+        use crate::judge::batch::judge;
+
+        let Some(reducer) = self.reducer else {
+            return Ok(());
+        };
+        let verdict = judgement.verdict;
+        // A candidate is kept only if it fails with the same verdict.
+        let minimal = crate::shrink::shrink(fs::read(&case.input)?, reducer, |candidate| {
+            fs::write(&case.input, candidate, true)?;
+            if let Some(validator) = &self.config.validator {
+                if crate::validator::validate(validator, &case.input, self.timelimit_generator)?
+                    .is_some()
+                {
+                    return Ok(false);
+                }
+            }
+            let Ok(case) = generate_output(case, &self.output_generator, self.timelimit_generator)
+            else {
+                return Ok::<_, Error>(false);
+            };
+            Ok(judge(self.command, case, &self.limits, temp_dir)?.verdict == verdict)
+        })?;
+        // Judges the minimal case again, to leave its intermediate files.
+        fs::write(&case.input, &minimal, true)?;
+        let case = generate_output(case, &self.output_generator, self.timelimit_generator)?;
+        judge(self.command, case, &self.limits, temp_dir)?;
+        Ok(())
+    }
+}
+
+pub(super) fn run(
+    args: &Args,
+    config: &crate::config::Problem,
+    compiled: &crate::config::Compiled,
+) -> Result<(), Error> {
+    use crate::shrink::Reducer;
 
     log::info!("[Batch Hack] Start");
-    let dir = &args.directory;
+    let command = args
+        .command
+        .as_ref()
+        .or(compiled.command.as_ref())
+        .or(config.command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-c"))?;
+    // The built-in generator from `--spec` takes the place of the input generator.
+    let source = match &args.spec {
+        Some(spec) => Source::Spec(crate::spec::load(spec)?),
+        None => Source::Command(
+            args.input_generator
+                .as_ref()
+                .or(config.input_generator.as_ref())
+                .ok_or(Error::OptionNotSpecified("-i"))?,
+        ),
+    };
+    let output_generator = args
+        .output_generator
+        .to_owned()
+        .or(config.output_generator.to_owned());
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
     if !dir.exists() {
         return Err(Error::CasedirNotFound(dir.to_owned()));
     }
@@ -41,28 +231,34 @@ pub(super) fn run(args: &Args) -> Result<(), Error> {
         return Err(Error::CasedirNotDir(dir.to_owned()));
     }
 
-    let timelimit_program = args.timelimit_ms.unwrap_or(10000);
-    let timelimit_generator = timelimit_program * 10;
-    let mut trial = 0;
-    let (temp_case, final_case) = crate::testcase::new_hackcase(dir);
-    loop {
-        trial += 1;
-        log::info!("[Batch Hack][Trial {}] Start", trial);
-        let case = generate(
-            &temp_case,
-            &args.input_generator,
-            &args.output_generator,
-            timelimit_generator,
-        )?;
-        let temp_dir = std::env::temp_dir();
-        let verdict = judge(&args.command, case, timelimit_program, &temp_dir)?;
-        log::info!("[Batch Hack][Trial {}] End: {}", trial, verdict);
-        if !verdict.is_ac() {
-            temp_case.copy_to_with_intermediate_files(&final_case, &temp_dir, dir)?;
-            break;
-        }
-    }
-
+    let limits = config.limits(
+        compiled.tl_scale,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    let timelimit_generator = limits.time_ms * 10;
+    let reducer = args.shrink.then(|| match &args.shrinker {
+        Some(shrinker) => Reducer::Command(shrinker, timelimit_generator),
+        None => Reducer::Builtin,
+    });
+    let hunter = Hunter {
+        args,
+        config,
+        command,
+        source,
+        output_generator,
+        limits,
+        timelimit_generator,
+        reducer,
+    };
+    crate::commands::hack::hunt(
+        &hunter,
+        &args.search,
+        dir,
+        config.validator.as_ref(),
+        timelimit_generator,
+    )?;
     log::info!("[Batch Hack] End",);
     Ok(())
 }
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = true, short = 'c')]
+    pub(super) commands: Vec<String>,
+    #[arg(required = false, short = 'i')]
+    input_generator: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
+    /// Parameter schedule of the input generator (e.g. `n=1..10,100`), passed as `-<name> <value>`
+    ///
+    /// The k-th trial takes the k-th value, and the last one after the schedule runs out.
+    /// Without the name (e.g. `1..10`), only the value is passed.
+    #[arg(required = false, long = "param")]
+    params: Vec<crate::generator::Param>,
+    #[command(flatten)]
+    search: crate::commands::hack::SearchArgs,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("At least 2 solutions are required, but {0} given.")]
+    TooFewSolutions(usize),
+    #[error("`{0}` is not found.")]
+    CasedirNotFound(PathBuf),
+    #[error("`{0}` is not a directory.")]
+    CasedirNotDir(PathBuf),
+    #[error("Generation failed.")]
+    GenerationFailed(#[from] crate::generator::Error),
+    #[error("Comparison failed.")]
+    CompareFailed(#[from] crate::compare::Error),
+    #[error("Validation failed.")]
+    ValidationFailed(#[from] crate::validator::Error),
+    #[error("Failed to save to the corpus.")]
+    CorpusFailed(#[from] crate::corpus::Error),
+    #[error("Cannot create a temporary directory.")]
+    TempDir(#[from] std::io::Error),
+    #[error("Failed to copy testcase.")]
+    TestcaseCopy(#[from] crate::testcase::Error),
+}
+
+struct Hunter<'a> {
+    args: &'a Args,
+    dir: &'a Path,
+    input_generator: &'a str,
+    limits: crate::judge::Limits,
+    timelimit_generator: u64,
+}
+impl crate::commands::hack::Hunter for Hunter<'_> {
+    type Error = Error;
+
+    const TAG: &'static str = "Compare Hack";
+
+    fn params(&self, trial: usize) -> String {
+        crate::generator::describe_params(&self.args.params, trial)
+    }
+
+    fn generate(
+        &self,
+        trial: usize,
+        temp_case: &crate::testcase::Testcase,
+    ) -> Result<crate::testcase::Testcase, Error> {
+        // There is no trusted output to generate.
+        Ok(crate::generator::generate(
+            temp_case,
+            self.input_generator,
+            &crate::generator::param_args(&self.args.params, trial),
+            &None,
+            self.timelimit_generator,
+        )?)
+    }
+
+    /// A disagreement is judged as WA, with the longest elapsed time among the solutions.
+    fn judge(
+        &self,
+        case: &crate::testcase::Testcase,
+        temp_dir: &Path,
+    ) -> Result<crate::judge::Judgement, Error> {
+        use crate::judge::Verdict;
+
+        let comparison =
+            crate::compare::compare(&self.args.commands, case, &self.limits, temp_dir)?;
+        Ok(crate::judge::Judgement {
+            verdict: if comparison.agrees() {
+                Verdict::Ac
+            } else {
+                Verdict::Wa
+            },
+            elapsed_ms: comparison.elapsed_ms,
+            cpu_ms: None,
+            termination: None,
+        })
+    }
+
+    fn describe(&self, judgement: &crate::judge::Judgement) -> String {
+        if judgement.verdict.is_ac() {
+            "Agree".to_owned()
+        } else {
+            "Disagree".to_owned()
+        }
+    }
+
+    fn saved(
+        &self,
+        case: &crate::testcase::Testcase,
+        saved: &crate::testcase::Testcase,
+        temp_dir: &Path,
+    ) -> Result<(), Error> {
+        crate::compare::copy_outputs(case, temp_dir, saved, self.dir, self.args.commands.len())?;
+        Ok(())
+    }
+}
+
+/// Generates inputs until the solutions disagree, and saves the input with each output.
+pub(super) fn run(
+    args: &Args,
+    config: &crate::config::Problem,
+    compiled: &crate::config::Compiled,
+) -> Result<(), Error> {
+    log::info!("[Compare Hack] Start");
+    if args.commands.len() < 2 {
+        return Err(Error::TooFewSolutions(args.commands.len()));
+    }
+    let input_generator = args
+        .input_generator
+        .as_ref()
+        .or(config.input_generator.as_ref())
+        .ok_or(Error::OptionNotSpecified("-i"))?;
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
+    if !dir.exists() {
+        return Err(Error::CasedirNotFound(dir.to_owned()));
+    }
+    if !dir.is_dir() {
+        return Err(Error::CasedirNotDir(dir.to_owned()));
+    }
+
+    for (i, command) in args.commands.iter().enumerate() {
+        log::info!("[Compare Hack] #{}: {}", i + 1, command);
+    }
+    let limits = config.limits(
+        compiled.tl_scale,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    let timelimit_generator = limits.time_ms * 10;
+    let hunter = Hunter {
+        args,
+        dir,
+        input_generator,
+        limits,
+        timelimit_generator,
+    };
+    crate::commands::hack::hunt(
+        &hunter,
+        &args.search,
+        dir,
+        config.validator.as_ref(),
+        timelimit_generator,
+    )?;
+    log::info!("[Compare Hack] End");
+    Ok(())
+}
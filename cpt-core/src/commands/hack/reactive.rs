@@ -1,21 +1,36 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Default)]
 pub(crate) struct Args {
-    #[arg(required = true, short = 'c')]
-    command: String,
-    #[arg(required = true, short = 'j')]
-    judge: String,
-    #[arg(required = true, short = 'i')]
-    input_generator: String,
-    #[arg(required = true, short = 'd', value_hint(clap::ValueHint::FilePath))]
-    directory: PathBuf,
+    #[arg(required = false, short = 'c')]
+    pub(super) command: Option<String>,
+    #[arg(required = false, short = 'j')]
+    judge: Option<String>,
+    #[arg(required = false, short = 'i')]
+    input_generator: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
     #[arg(required = false, short = 't')]
     timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
+    /// Parameter schedule of the input generator (e.g. `n=1..10,100`), passed as `-<name> <value>`
+    ///
+    /// The k-th trial takes the k-th value, and the last one after the schedule runs out.
+    /// Without the name (e.g. `1..10`), only the value is passed.
+    #[arg(required = false, long = "param")]
+    params: Vec<crate::generator::Param>,
+    #[command(flatten)]
+    search: crate::commands::hack::SearchArgs,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
     #[error("`{0}` is not found.")]
     CasedirNotFound(PathBuf),
     #[error("`{0}` is not a directory.")]
@@ -24,54 +39,120 @@ pub(crate) enum Error {
     GenerationFailed(#[source] crate::generator::Error),
     #[error("Judge failed.")]
     JudgeFailed(#[source] crate::judge::reactive::Error),
+    #[error("Validation failed.")]
+    ValidationFailed(#[from] crate::validator::Error),
+    #[error("Failed to save to the corpus.")]
+    CorpusFailed(#[from] crate::corpus::Error),
+    #[error("Cannot create a temporary directory.")]
+    TempDir(#[from] std::io::Error),
     #[error("Failed to copy testcase.")]
     TestcaseCopy(#[from] crate::testcase::Error),
 }
 
-pub(super) fn run(args: &Args) -> Result<(), Error> {
-    use crate::generator::generate;
-    use crate::judge::reactive::judge;
+struct Hunter<'a> {
+    args: &'a Args,
+    command: &'a str,
+    judge_command: &'a str,
+    input_generator: &'a str,
+    limits: crate::judge::Limits,
+    timelimit_generator: u64,
+}
+impl crate::commands::hack::Hunter for Hunter<'_> {
+    type Error = Error;
 
-    log::info!("[Reactive Hack] Start");
-    let dir = &args.directory;
-    if !dir.exists() {
-        return Err(Error::CasedirNotFound(dir.to_owned()));
-    }
-    if !dir.is_dir() {
-        return Err(Error::CasedirNotDir(dir.to_owned()));
+    const TAG: &'static str = "Reactive Hack";
+
+    fn params(&self, trial: usize) -> String {
+        crate::generator::describe_params(&self.args.params, trial)
     }
 
-    let timelimit_program = args.timelimit_ms.unwrap_or(10000);
-    let timelimit_generator = timelimit_program * 10;
-    let mut trial = 0;
-    let (temp_case, final_case) = crate::testcase::new_hackcase(dir);
-    loop {
-        trial += 1;
-        log::info!("[Batch Hack][Trial {}] Start", trial);
-        let case = generate(
-            &temp_case,
-            &args.input_generator,
+    fn generate(
+        &self,
+        trial: usize,
+        temp_case: &crate::testcase::Testcase,
+    ) -> Result<crate::testcase::Testcase, Error> {
+        crate::generator::generate(
+            temp_case,
+            self.input_generator,
+            &crate::generator::param_args(&self.args.params, trial),
             &None,
-            timelimit_generator,
+            self.timelimit_generator,
         )
-        .map_err(Error::GenerationFailed)?;
+        .map_err(Error::GenerationFailed)
+    }
 
-        let temp_dir = std::env::temp_dir();
-        let verdict = judge(
-            &args.command,
-            &args.judge,
-            case,
-            timelimit_program,
-            &temp_dir,
+    fn judge(
+        &self,
+        case: &crate::testcase::Testcase,
+        temp_dir: &Path,
+    ) -> Result<crate::judge::Judgement, Error> {
+        crate::judge::reactive::judge(
+            self.command,
+            self.judge_command,
+            case.to_owned(),
+            &self.limits,
+            temp_dir,
         )
-        .map_err(Error::JudgeFailed)?;
-        log::info!("[Batch Hack][Trial {}] End: {}", trial, verdict);
-        if !verdict.is_ac() {
-            temp_case.copy_to_with_intermediate_files(&final_case, &temp_dir, dir)?;
-            break;
-        }
+        .map_err(Error::JudgeFailed)
+    }
+}
+
+pub(super) fn run(
+    args: &Args,
+    config: &crate::config::Problem,
+    compiled: &crate::config::Compiled,
+) -> Result<(), Error> {
+    log::info!("[Reactive Hack] Start");
+    let command = args
+        .command
+        .as_ref()
+        .or(compiled.command.as_ref())
+        .or(config.command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-c"))?;
+    let judge_command = args
+        .judge
+        .as_ref()
+        .or(config.judge_command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-j"))?;
+    let input_generator = args
+        .input_generator
+        .as_ref()
+        .or(config.input_generator.as_ref())
+        .ok_or(Error::OptionNotSpecified("-i"))?;
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
+    if !dir.exists() {
+        return Err(Error::CasedirNotFound(dir.to_owned()));
+    }
+    if !dir.is_dir() {
+        return Err(Error::CasedirNotDir(dir.to_owned()));
     }
 
+    let limits = config.limits(
+        compiled.tl_scale,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    let timelimit_generator = limits.time_ms * 10;
+    let hunter = Hunter {
+        args,
+        command,
+        judge_command,
+        input_generator,
+        limits,
+        timelimit_generator,
+    };
+    crate::commands::hack::hunt(
+        &hunter,
+        &args.search,
+        dir,
+        config.validator.as_ref(),
+        timelimit_generator,
+    )?;
     log::info!("[Reactive Hack] End",);
     Ok(())
 }
@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+
+use itertools::Itertools;
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = false, short = 'c')]
+    pub(super) command: Option<String>,
+    /// Special judge command. Without it, the outputs are compared exactly
+    #[arg(required = false, short = 'j')]
+    judge: Option<String>,
+    /// Runs the judge command as a reactive judge
+    #[arg(long = "reactive", requires = "judge")]
+    reactive: bool,
+    #[arg(long = "corpus", value_hint(clap::ValueHint::DirPath))]
+    corpus: PathBuf,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("No case found in `{0}`.")]
+    CaseNotFound(PathBuf),
+    #[error("Cannot read the corpus.")]
+    CorpusFailed(#[from] crate::corpus::Error),
+    #[error("Cannot create a temporary directory.")]
+    TempDir(#[source] std::io::Error),
+    #[error("Judge failed.")]
+    BatchJudgeFailed(#[from] crate::judge::batch::Error),
+    #[error("Judge failed.")]
+    SpecialJudgeFailed(#[from] crate::judge::special::Error),
+    #[error("Judge failed.")]
+    ReactiveJudgeFailed(#[from] crate::judge::reactive::Error),
+    #[error("{0} regression(s) found.")]
+    Regressed(usize),
+}
+
+/// Re-runs the program against every input of the corpus saved by `--corpus`,
+/// and reports the verdicts changed from the recorded ones.
+pub(super) fn run(
+    args: &Args,
+    config: &crate::config::Problem,
+    compiled: &crate::config::Compiled,
+) -> Result<(), Error> {
+    use strum::{EnumCount, IntoEnumIterator};
+
+    use crate::config::JudgeKind;
+    use crate::judge::{batch, reactive, special, Verdict};
+
+    log::info!("[Replay] Start");
+    let command = args
+        .command
+        .as_ref()
+        .or(compiled.command.as_ref())
+        .or(config.command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-c"))?;
+    // `-j` alone selects the special judge, unless `cpt.toml` says the problem is reactive.
+    let kind = match (args.reactive, &args.judge, config.judge.unwrap_or_default()) {
+        (true, _, _) | (false, Some(_), JudgeKind::Reactive) => JudgeKind::Reactive,
+        (false, Some(_), _) => JudgeKind::Special,
+        (false, None, kind) => kind,
+    };
+    let judge_command = match kind {
+        JudgeKind::Batch => None,
+        JudgeKind::Special | JudgeKind::Reactive => Some(
+            args.judge
+                .as_ref()
+                .or(config.judge_command.as_ref())
+                .ok_or(Error::OptionNotSpecified("-j"))?,
+        ),
+    };
+    let mut cases = crate::testcase::collect(&args.corpus);
+    if cases.is_empty() {
+        return Err(Error::CaseNotFound(args.corpus.to_owned()));
+    }
+    cases.sort_by(|a, b| a.casename.cmp(&b.casename));
+    let recorded = crate::corpus::load_verdicts(&args.corpus)?;
+
+    let limits = config.limits(
+        compiled.tl_scale,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    cpt_stdx::tempfile::remove_stale();
+    let temp_dir = cpt_stdx::tempfile::tempdir().map_err(Error::TempDir)?;
+    let mut results = [0; Verdict::COUNT];
+    let mut regressions = 0;
+    for case in cases {
+        let casename = case.casename.to_owned();
+        let verdict = match (kind, judge_command) {
+            (JudgeKind::Special, Some(judge_command)) => {
+                special::judge(command, judge_command, case, &limits, temp_dir.path())?.verdict
+            }
+            (JudgeKind::Reactive, Some(judge_command)) => {
+                reactive::judge(command, judge_command, case, &limits, temp_dir.path())?.verdict
+            }
+            _ => batch::judge(command, case, &limits, temp_dir.path())?.verdict,
+        };
+        results[verdict as usize] += 1;
+        match recorded.get(&casename) {
+            Some(&before) if before != verdict && before.is_ac() => {
+                log::warn!(
+                    "[Replay][{}] Regression: {} -> {}",
+                    casename,
+                    before,
+                    verdict
+                );
+                regressions += 1;
+            }
+            Some(&before) if before != verdict => {
+                log::info!("[Replay][{}] Changed: {} -> {}", casename, before, verdict);
+            }
+            _ => {}
+        }
+    }
+    log::info!(
+        "[Replay] End ({}) Regressions: {}",
+        Verdict::iter()
+            .map(|verdict| format!("{}:{}", verdict.to_owned(), results[verdict as usize]))
+            .join(","),
+        regressions
+    );
+    if regressions > 0 {
+        return Err(Error::Regressed(regressions));
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = false, short = 'c')]
+    command: Option<String>,
+    #[arg(required = false, short = 'i', value_hint(clap::ValueHint::FilePath))]
+    input: Option<PathBuf>,
+    #[arg(required = false, short = 'e', value_hint(clap::ValueHint::FilePath))]
+    expect: Option<PathBuf>,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("`{0}` is not found.")]
+    InputNotFound(PathBuf),
+    #[error("Failed to execute program.")]
+    Program(#[source] cpt_stdx::process::Error),
+    #[error("Cannot read `expect` file.")]
+    ReadExpectation(#[source] cpt_stdx::fs::Error),
+    #[error("Cannot write the output.")]
+    WriteOutput(#[source] std::io::Error),
+}
+
+/// Runs the program once and reports its output and statistics.
+///
+/// The input is read from the file given by `-i`, or from the terminal otherwise.
+/// The output is passed through as it is, except with `-e`, where it is written
+/// once the program ends, to be compared.
+pub(super) fn run(args: &Args, config: &crate::config::Problem) -> Result<(), Error> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    use cpt_stdx::fs;
+    use cpt_stdx::process::{
+        Capture, CaptureRedirection, Captured, Command, Status, StatusSummary,
+    };
+
+    use crate::judge::batch::compare;
+
+    log::info!("[Run] Start");
+    let command = args
+        .command
+        .as_ref()
+        .or(config.command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-c"))?;
+    let stdin = match &args.input {
+        Some(input) => {
+            Stdio::from(fs::open(input).map_err(|_| Error::InputNotFound(input.to_owned()))?)
+        }
+        None => Stdio::inherit(),
+    };
+    let timelimit = config.limits(None, args.timelimit_ms, None, None).time_ms;
+    let Captured {
+        status: Status { summary, detail },
+        stdout,
+        ..
+    } = Command::from(command)
+        .exec_capture(
+            CaptureRedirection {
+                stdin,
+                stdout: match &args.expect {
+                    Some(_) => Capture::Memory { tail: None },
+                    None => Capture::Inherit,
+                },
+                stderr: Capture::Inherit,
+                limit_bytes: None,
+            },
+            timelimit,
+            false,
+        )
+        .map_err(Error::Program)?;
+
+    std::io::stdout()
+        .write_all(&stdout)
+        .and_then(|_| std::io::stdout().flush())
+        .map_err(Error::WriteOutput)?;
+    log::info!("[Run] Time: {}ms/{}ms", detail.elapsed_ms, timelimit);
+    if let Some(memory_kb) = detail.memory_kb {
+        log::info!("[Run] Memory: {}KB", memory_kb);
+    }
+    match detail.termination() {
+        Some(termination) => log::info!("[Run] Exit: {}", termination),
+        None => log::info!("[Run] Exit: killed"),
+    }
+    match summary {
+        StatusSummary::Success => {
+            if let Some(expect_path) = &args.expect {
+                let expect = fs::read(expect_path).map_err(Error::ReadExpectation)?;
+                if let Some(diff_lines) = compare(&expect, &String::from_utf8_lossy(&stdout)) {
+                    log::warn!("[Run] WA");
+                    diff_lines.iter().for_each(|line| log::warn!("{}", line));
+                } else {
+                    log::info!("[Run] AC");
+                }
+            }
+        }
+        StatusSummary::Aborted => log::warn!("[Run] RE ({})", detail.termination().unwrap()),
+        StatusSummary::Timeout => log::warn!("[Run] TLE"),
+        StatusSummary::OutputLimitExceeded => log::warn!("[Run] OLE"),
+    }
+    log::info!("[Run] End");
+    Ok(())
+}
@@ -1,6 +1,7 @@
 pub mod batch;
 pub mod reactive;
 pub mod special;
+mod watch;
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
@@ -10,6 +11,36 @@ pub(crate) enum Error {
     Special(#[from] crate::commands::test::special::Error),
     #[error("Reactive Test failed.")]
     Reactive(#[from] crate::commands::test::reactive::Error),
+    #[error("Cannot build the source.")]
+    Compile(#[from] crate::language::Error),
+    #[error("Watch failed.")]
+    Watch(#[from] crate::commands::test::watch::Error),
+}
+
+/// Without the subcommand, the variant is selected by `judge` in `cpt.toml`.
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(
+        required = false,
+        short = 's',
+        global = true,
+        value_hint(clap::ValueHint::FilePath)
+    )]
+    source: Option<std::path::PathBuf>,
+    #[arg(required = false, long = "validator", global = true)]
+    validator: Option<String>,
+    /// Factor applied to the time limit, on top of the per-language and machine factors
+    #[arg(
+        required = false,
+        long = "tl-scale",
+        global = true,
+        value_parser = crate::config::parse_tl_scale
+    )]
+    tl_scale: Option<f64>,
+    #[arg(long = "watch", global = true)]
+    watch: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -22,18 +53,74 @@ pub(crate) enum Command {
     Reactive(crate::commands::test::reactive::Args),
 }
 impl Command {
-    pub(crate) fn run(&self) -> Result<(), Error> {
-        use crate::commands::test::{batch, reactive, special};
+    /// Solution command given by `-c`.
+    fn command(&self) -> Option<&String> {
         match self {
-            Command::Batch(args) => {
-                batch::run(args)?;
+            Command::Batch(args) => args.command.as_ref(),
+            Command::Special(args) => args.command.as_ref(),
+            Command::Reactive(args) => args.command.as_ref(),
+        }
+    }
+}
+impl Args {
+    pub(crate) fn run(&self, config: &crate::config::Problem) -> Result<(), Error> {
+        use crate::commands::test::{batch, reactive, special};
+        use crate::config::JudgeKind;
+
+        if self.watch {
+            let source = self.source.as_ref().or(config.source.as_ref());
+            let mut targets = match &self.command {
+                Some(Command::Batch(args)) => batch::dependencies(args, config, source.is_none()),
+                Some(Command::Special(args)) => {
+                    special::dependencies(args, config, source.is_none())
+                }
+                Some(Command::Reactive(args)) => {
+                    reactive::dependencies(args, config, source.is_none())
+                }
+                None => match config.judge.unwrap_or_default() {
+                    JudgeKind::Batch => {
+                        batch::dependencies(&Default::default(), config, source.is_none())
+                    }
+                    JudgeKind::Special => {
+                        special::dependencies(&Default::default(), config, source.is_none())
+                    }
+                    JudgeKind::Reactive => {
+                        reactive::dependencies(&Default::default(), config, source.is_none())
+                    }
+                },
+            };
+            targets.extend(source.cloned());
+            watch::run(targets)?;
+            return Ok(());
+        }
+
+        let compiled = config.compile(
+            self.source.as_ref(),
+            self.command.as_ref().and_then(Command::command),
+        )?;
+        let mut config = config.clone();
+        if self.validator.is_some() {
+            config.validator = self.validator.to_owned();
+        }
+        if self.tl_scale.is_some() {
+            config.tl_scale = self.tl_scale;
+        }
+        let (config, compiled) = (&config, &compiled);
+        match &self.command {
+            Some(Command::Batch(args)) => {
+                batch::run(args, config, compiled)?;
             }
-            Command::Special(args) => {
-                special::run(args)?;
+            Some(Command::Special(args)) => {
+                special::run(args, config, compiled)?;
             }
-            Command::Reactive(args) => {
-                reactive::run(args)?;
+            Some(Command::Reactive(args)) => {
+                reactive::run(args, config, compiled)?;
             }
+            None => match config.judge.unwrap_or_default() {
+                JudgeKind::Batch => batch::run(&batch::Args::default(), config, compiled)?,
+                JudgeKind::Special => special::run(&special::Args::default(), config, compiled)?,
+                JudgeKind::Reactive => reactive::run(&reactive::Args::default(), config, compiled)?,
+            },
         }
         Ok(())
     }
@@ -2,18 +2,28 @@ use std::path::PathBuf;
 
 use itertools::Itertools;
 
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Default)]
 pub(crate) struct Args {
-    #[arg(required = true, short = 'c')]
-    command: String,
-    #[arg(required = true, short = 'd', value_hint(clap::ValueHint::FilePath))]
-    directory: PathBuf,
+    #[arg(required = false, short = 'c')]
+    pub(super) command: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
     #[arg(required = false, short = 't')]
     timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
+    /// Judge each case this number of times, and report the ones whose verdict or output changes
+    #[arg(long = "repeat", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    repeat: u64,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
     #[error("`{0}` is not found.")]
     CasedirNotFound(PathBuf),
     #[error("`{0}` is not a directory.")]
@@ -22,16 +32,60 @@ pub(crate) enum Error {
     CaseNotFound(PathBuf),
     #[error("Judge failed.")]
     JudgeFailed(#[from] crate::judge::batch::Error),
+    #[error("Validation failed.")]
+    ValidationFailed(#[from] crate::validator::Error),
+    #[error("Cannot read the output.")]
+    Output(#[from] cpt_stdx::fs::Error),
 }
 
-pub(super) fn run(args: &Args) -> Result<(), Error> {
+/// Paths the test depends on, watched by `--watch`.
+///
+/// The program is omitted with `with_program == false`, when it is built by the test itself.
+pub(super) fn dependencies(
+    args: &Args,
+    config: &crate::config::Problem,
+    with_program: bool,
+) -> Vec<PathBuf> {
+    use crate::commands::test::watch;
+
+    [
+        args.command
+            .as_ref()
+            .or(config.command.as_ref())
+            .filter(|_| with_program)
+            .map(|command| watch::program_path(command)),
+        args.directory
+            .as_ref()
+            .or(config.directory.as_ref())
+            .cloned(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+pub(super) fn run(
+    args: &Args,
+    config: &crate::config::Problem,
+    compiled: &crate::config::Compiled,
+) -> Result<(), Error> {
     use strum::{EnumCount, IntoEnumIterator};
 
     use crate::judge::batch::judge;
     use crate::judge::Verdict;
 
     log::info!("[Batch Test] Start");
-    let dir = &args.directory;
+    let command = args
+        .command
+        .as_ref()
+        .or(compiled.command.as_ref())
+        .or(config.command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-c"))?;
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
     if !dir.exists() {
         return Err(Error::CasedirNotFound(dir.to_owned()));
     }
@@ -44,17 +98,83 @@ pub(super) fn run(args: &Args) -> Result<(), Error> {
         return Err(Error::CaseNotFound(dir.to_owned()));
     }
 
-    let timelimit = args.timelimit_ms.unwrap_or(10000);
+    let limits = config.limits(
+        compiled.tl_scale,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    if let Some(validator) = &config.validator {
+        let invalid = crate::validator::validate_cases(validator, &cases, limits.time_ms * 10)?;
+        if invalid > 0 {
+            log::warn!("[Batch Test] {} invalid input(s) found", invalid);
+        }
+    }
     let mut results = [0; Verdict::COUNT];
+    let mut flaky = 0;
     for case in cases {
-        let verdict = judge(&args.command, case, timelimit, dir)?;
+        if compiled.error {
+            results[Verdict::Ce as usize] += 1;
+            continue;
+        }
+        if case.output.is_none() {
+            log::warn!(
+                "[Batch Test][{}] No expected output (`cpt gen-out` can generate it)",
+                case.casename
+            );
+        }
+        let actual = dir.join(case.casename.to_owned() + ".actual.txt");
+        let mut verdict = judge(command, case.to_owned(), &limits, dir)?.verdict;
+        let first = verdict;
+        let first_output = match args.repeat {
+            1 => String::new(),
+            _ => cpt_stdx::fs::read(&actual)?,
+        };
+        let mut is_flaky = false;
+        for run in 2..=args.repeat {
+            let rerun = judge(command, case.to_owned(), &limits, dir)?.verdict;
+            let output = cpt_stdx::fs::read(&actual)?;
+            let diff_lines = crate::judge::batch::compare(&first_output, &output);
+            if rerun != first {
+                log::warn!(
+                    "[Batch Test][{}] Flaky: {} -> {} (Run {})",
+                    case.casename,
+                    first,
+                    rerun,
+                    run
+                );
+            } else if diff_lines.is_some() {
+                log::warn!(
+                    "[Batch Test][{}] Flaky: the output changed (Run {})",
+                    case.casename,
+                    run
+                );
+            }
+            if rerun != first || diff_lines.is_some() {
+                diff_lines
+                    .unwrap_or_default()
+                    .iter()
+                    .for_each(|line| log::warn!("{}", line));
+                is_flaky = true;
+            }
+            // The case fails if any run fails.
+            if verdict.is_ac() {
+                verdict = rerun;
+            }
+        }
+        flaky += is_flaky as usize;
         results[verdict as usize] += 1;
     }
     log::info!(
-        "[Batch Test] End ({})",
+        "[Batch Test] End ({}){}",
         Verdict::iter()
             .map(|verdict| format!("{}:{}", verdict.to_owned(), results[verdict as usize]))
-            .join(",")
+            .join(","),
+        if args.repeat > 1 {
+            format!(" Flaky: {}", flaky)
+        } else {
+            String::new()
+        }
     );
     Ok(())
 }
@@ -2,20 +2,27 @@ use std::path::PathBuf;
 
 use itertools::Itertools;
 
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Default)]
 pub(crate) struct Args {
-    #[arg(required = true, short = 'c')]
-    command: String,
-    #[arg(required = true, short = 'j')]
-    judge: String,
-    #[arg(required = true, short = 'd', value_hint(clap::ValueHint::FilePath))]
-    directory: PathBuf,
+    #[arg(required = false, short = 'c')]
+    pub(super) command: Option<String>,
+    #[arg(required = false, short = 'j')]
+    judge: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
     #[arg(required = false, short = 't')]
     timelimit_ms: Option<u64>,
+    #[arg(required = false, short = 'm')]
+    memorylimit_mb: Option<u64>,
+    /// Output limit in MB, counting stdout and stderr separately
+    #[arg(required = false, long = "output-limit")]
+    outputlimit_mb: Option<u64>,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
     #[error("`{0}` is not found.")]
     CasedirNotFound(PathBuf),
     #[error("`{0}` is not a directory.")]
@@ -24,16 +31,67 @@ pub(crate) enum Error {
     CaseNotFound(PathBuf),
     #[error("Judge failed.")]
     JudgeFailed(#[from] crate::judge::reactive::Error),
+    #[error("Validation failed.")]
+    ValidationFailed(#[from] crate::validator::Error),
 }
 
-pub(super) fn run(args: &Args) -> Result<(), Error> {
+/// Paths the test depends on, watched by `--watch`.
+///
+/// The program is omitted with `with_program == false`, when it is built by the test itself.
+pub(super) fn dependencies(
+    args: &Args,
+    config: &crate::config::Problem,
+    with_program: bool,
+) -> Vec<PathBuf> {
+    use crate::commands::test::watch;
+
+    [
+        args.command
+            .as_ref()
+            .or(config.command.as_ref())
+            .filter(|_| with_program)
+            .map(|command| watch::program_path(command)),
+        args.judge
+            .as_ref()
+            .or(config.judge_command.as_ref())
+            .map(|judge| watch::program_path(judge)),
+        args.directory
+            .as_ref()
+            .or(config.directory.as_ref())
+            .cloned(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+pub(super) fn run(
+    args: &Args,
+    config: &crate::config::Problem,
+    compiled: &crate::config::Compiled,
+) -> Result<(), Error> {
     use strum::{EnumCount, IntoEnumIterator};
 
     use crate::judge::reactive::judge;
     use crate::judge::Verdict;
 
     log::info!("[Reactive Test] Start");
-    let dir = &args.directory;
+    let command = args
+        .command
+        .as_ref()
+        .or(compiled.command.as_ref())
+        .or(config.command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-c"))?;
+    let judge_command = args
+        .judge
+        .as_ref()
+        .or(config.judge_command.as_ref())
+        .ok_or(Error::OptionNotSpecified("-j"))?;
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
     if !dir.exists() {
         return Err(Error::CasedirNotFound(dir.to_owned()));
     }
@@ -46,10 +104,25 @@ pub(super) fn run(args: &Args) -> Result<(), Error> {
         return Err(Error::CaseNotFound(dir.to_owned()));
     }
 
-    let timelimit = args.timelimit_ms.unwrap_or(10000);
+    let limits = config.limits(
+        compiled.tl_scale,
+        args.timelimit_ms,
+        args.outputlimit_mb,
+        args.memorylimit_mb,
+    );
+    if let Some(validator) = &config.validator {
+        let invalid = crate::validator::validate_cases(validator, &cases, limits.time_ms * 10)?;
+        if invalid > 0 {
+            log::warn!("[Reactive Test] {} invalid input(s) found", invalid);
+        }
+    }
     let mut results = [0; Verdict::COUNT];
     for case in cases {
-        let verdict = judge(&args.command, &args.judge, case, timelimit, dir)?;
+        if compiled.error {
+            results[Verdict::Ce as usize] += 1;
+            continue;
+        }
+        let verdict = judge(command, judge_command, case, &limits, dir)?.verdict;
         results[verdict as usize] += 1;
     }
     log::info!(
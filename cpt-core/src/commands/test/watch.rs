@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Quiet period after the last change, before re-running.
+const DEBOUNCE_MS: u64 = 200;
+/// How long a cancelled test is given to stop its programs, before it is killed.
+const TERMINATE_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Cannot watch `{0}`.")]
+    Watch(PathBuf, #[source] notify::Error),
+    #[error("Failed to start the test.")]
+    Spawn(#[source] cpt_stdx::process::Error),
+}
+
+/// Path of the program of `command`, which is watched if it is a local file.
+pub(super) fn program_path(command: &str) -> PathBuf {
+    PathBuf::from(cpt_stdx::process::Command::from(command).program)
+}
+
+/// Whether `path` is written by the judge itself, which must not trigger a re-run.
+fn is_judge_output(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    [".actual.txt", ".debug.txt", ".judge.txt"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+/// Runs the test as a child `cpt` process, and restarts it whenever any of `targets` changes.
+///
+/// The test in progress is terminated on a change, together with the programs it runs.
+/// Runs until interrupted.
+pub(super) fn run(targets: Vec<PathBuf>) -> Result<(), Error> {
+    use std::process::Stdio;
+
+    use notify::{RecursiveMode, Watcher};
+
+    use cpt_stdx::process::{Command, IoRedirection};
+
+    let targets = targets
+        .into_iter()
+        .filter(|path| path.exists())
+        .filter_map(|path| std::path::absolute(path).ok())
+        .collect::<Vec<_>>();
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| Error::Watch(PathBuf::from("."), e))?;
+    for target in &targets {
+        // A file is watched through its directory, to follow editors replacing the file on save.
+        let (path, mode) = if target.is_dir() {
+            (target.as_path(), RecursiveMode::Recursive)
+        } else {
+            (
+                target.parent().unwrap_or(target),
+                RecursiveMode::NonRecursive,
+            )
+        };
+        watcher
+            .watch(path, mode)
+            .map_err(|e| Error::Watch(target.to_owned(), e))?;
+        log::info!("[Watch] {}", target.display());
+    }
+    let is_relevant = |event: &notify::Event| {
+        !event.kind.is_access()
+            && event.paths.iter().any(|path| {
+                !is_judge_output(path)
+                    && targets.iter().any(|target| {
+                        path == target || (target.is_dir() && path.starts_with(target))
+                    })
+            })
+    };
+
+    // Re-invokes this command line without `--watch`.
+    let mut args = std::env::args();
+    let program = args.next().unwrap_or_default();
+    let program = std::env::current_exe()
+        .map(|exe| exe.display().to_string())
+        .unwrap_or(program);
+    let command = Command {
+        program,
+        args: args.filter(|arg| arg != "--watch").collect(),
+    };
+    let _runtime = cpt_stdx::process::runtime().enter();
+    loop {
+        let mut child = Some(
+            command
+                .spawn(IoRedirection {
+                    stdin: Stdio::null(),
+                    stdout: Stdio::inherit(),
+                    stderr: Stdio::inherit(),
+                })
+                .map_err(Error::Spawn)?,
+        );
+        // Waits for a relevant change, reporting the end of the test meanwhile.
+        loop {
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(Ok(event)) if is_relevant(&event) => {
+                    log::info!("[Watch] Changed: {}", event.paths[0].display());
+                    break;
+                }
+                Ok(_) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+            if child.as_ref().is_some_and(|child| child.has_exited()) {
+                child = None;
+                log::info!("[Watch] Waiting for changes...");
+            }
+        }
+        // Debounces rapid saves.
+        while rx.recv_timeout(Duration::from_millis(DEBOUNCE_MS)).is_ok() {}
+        // The test stops its programs by itself, which run in their own process groups.
+        if let Some(mut child) = child.take() {
+            cpt_stdx::process::runtime().block_on(child.terminate(TERMINATE_GRACE));
+            log::info!("[Watch] Cancelled the running test");
+        }
+        log::info!("[Watch] Restart");
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct Args {
+    #[arg(required = false, long = "validator")]
+    validator: Option<String>,
+    #[arg(required = false, short = 'd', value_hint(clap::ValueHint::FilePath))]
+    directory: Option<PathBuf>,
+    #[arg(required = false, short = 't')]
+    timelimit_ms: Option<u64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is specified neither by the option nor by `cpt.toml`.")]
+    OptionNotSpecified(&'static str),
+    #[error("`{0}` is not found.")]
+    CasedirNotFound(PathBuf),
+    #[error("`{0}` is not a directory.")]
+    CasedirNotDir(PathBuf),
+    #[error("No case found in `{0}`.")]
+    CaseNotFound(PathBuf),
+    #[error("Validation failed.")]
+    ValidationFailed(#[from] crate::validator::Error),
+    #[error("{0} invalid input(s) found.")]
+    InvalidInput(usize),
+}
+
+/// Validates every input in the testcase directory.
+///
+/// Fails if any input is invalid.
+pub(super) fn run(args: &Args, config: &crate::config::Problem) -> Result<(), Error> {
+    use crate::validator::validate_cases;
+
+    log::info!("[Validate] Start");
+    let validator = args
+        .validator
+        .as_ref()
+        .or(config.validator.as_ref())
+        .ok_or(Error::OptionNotSpecified("--validator"))?;
+    let dir = args
+        .directory
+        .as_ref()
+        .or(config.directory.as_ref())
+        .ok_or(Error::OptionNotSpecified("-d"))?;
+    if !dir.exists() {
+        return Err(Error::CasedirNotFound(dir.to_owned()));
+    }
+    if !dir.is_dir() {
+        return Err(Error::CasedirNotDir(dir.to_owned()));
+    }
+
+    let cases = crate::testcase::collect(dir);
+    if cases.is_empty() {
+        return Err(Error::CaseNotFound(dir.to_owned()));
+    }
+
+    let timelimit = args.timelimit_ms.or(config.timelimit_ms).unwrap_or(10000);
+    let invalid = validate_cases(validator, &cases, timelimit)?;
+    log::info!(
+        "[Validate] End (Valid:{},Invalid:{})",
+        cases.len() - invalid,
+        invalid
+    );
+    if invalid > 0 {
+        return Err(Error::InvalidInput(invalid));
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use itertools::Itertools;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Judge failed.")]
+    JudgeFailed(#[from] crate::judge::batch::Error),
+    #[error("Cannot read the output.")]
+    Output(#[from] cpt_stdx::fs::Error),
+}
+
+/// Case of the `no`-th solution (1-indexed) on `case`, whose output is `<casename>.<no>.actual.txt`.
+fn solution_case(case: &crate::testcase::Testcase, no: usize) -> crate::testcase::Testcase {
+    crate::testcase::Testcase {
+        casename: format!("{}.{}", case.casename, no),
+        input: case.input.to_owned(),
+        output: None,
+    }
+}
+
+/// Solutions grouped by their output, as 1-indexed numbers.
+///
+/// Solutions which did not end successfully are grouped by the verdict instead.
+#[derive(Debug)]
+pub(crate) struct Comparison {
+    groups: Vec<Vec<usize>>,
+    /// Longest elapsed time among the solutions.
+    pub(crate) elapsed_ms: u64,
+}
+impl Comparison {
+    pub(crate) fn agrees(&self) -> bool {
+        self.groups.len() <= 1
+    }
+}
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let groups = self.groups.iter().map(|group| {
+            format!(
+                "{{{}}}",
+                group.iter().map(|no| format!("#{}", no)).join(",")
+            )
+        });
+        write!(f, "{}", groups.format(" / "))
+    }
+}
+
+/// Runs each of `commands` on `case`, and groups them by identical output.
+///
+/// The output of the `i`-th solution is left as `<casename>.<i>.actual.txt` in `dir`.
+pub(crate) fn compare(
+    commands: &[String],
+    case: &crate::testcase::Testcase,
+    limits: &crate::judge::Limits,
+    dir: &Path,
+) -> Result<Comparison, Error> {
+    use cpt_stdx::fs;
+
+    use crate::judge::batch::judge;
+
+    let mut keys: Vec<(Result<String, crate::judge::Verdict>, Vec<usize>)> = Vec::new();
+    let mut elapsed_ms = 0;
+    for (i, command) in commands.iter().enumerate() {
+        let solution = solution_case(case, i + 1);
+        let judgement = judge(command, solution.to_owned(), limits, dir)?;
+        elapsed_ms = elapsed_ms.max(judgement.elapsed_ms);
+        let key = match judgement.verdict {
+            crate::judge::Verdict::Ac => Ok(fs::read(dir.join(solution.casename + ".actual.txt"))?),
+            verdict => Err(verdict),
+        };
+        match keys.iter_mut().find(|(other, _)| *other == key) {
+            Some((_, group)) => group.push(i + 1),
+            None => keys.push((key, vec![i + 1])),
+        }
+    }
+    let comparison = Comparison {
+        groups: keys.iter().map(|(_, group)| group.to_owned()).collect(),
+        elapsed_ms,
+    };
+    if comparison.agrees() {
+        log::info!("[Compare][{}] Agree", case.casename);
+    } else {
+        log::warn!("[Compare][{}] Disagree: {}", case.casename, comparison);
+        // Shows how the first two outputs differ.
+        if let [(Ok(first), _), (Ok(second), _), ..] = keys.as_slice() {
+            for line in crate::judge::batch::compare(first, second).unwrap_or_default() {
+                log::warn!("{}", line);
+            }
+        }
+    }
+    Ok(comparison)
+}
+
+/// Copies the outputs of `count` solutions on `case` in `dir` as the ones of `target` in `target_dir`.
+pub(crate) fn copy_outputs(
+    case: &crate::testcase::Testcase,
+    dir: &Path,
+    target: &crate::testcase::Testcase,
+    target_dir: &Path,
+    count: usize,
+) -> Result<(), Error> {
+    for no in 1..=count {
+        let output = dir.join(solution_case(case, no).casename + ".actual.txt");
+        if output.exists() {
+            cpt_stdx::fs::copy(
+                &output,
+                target_dir.join(solution_case(target, no).casename + ".actual.txt"),
+            )?;
+        }
+    }
+    Ok(())
+}
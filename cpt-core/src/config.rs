@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILENAME: &str = "cpt.toml";
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Cannot read `{0}`.")]
+    Read(PathBuf, #[source] cpt_stdx::fs::Error),
+    #[error("Cannot parse `{0}`.")]
+    Parse(PathBuf, #[source] toml::de::Error),
+}
+
+/// Judge type of a problem, which selects the `test`/`hack` variant.
+#[derive(serde::Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum JudgeKind {
+    #[default]
+    Batch,
+    Special,
+    Reactive,
+}
+
+/// Per-problem defaults. Every field can be overridden by the command-line options.
+#[derive(serde::Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Problem {
+    /// Solution command (`-c`).
+    pub(crate) command: Option<String>,
+    pub(crate) judge: Option<JudgeKind>,
+    /// Judge command (`-j`).
+    pub(crate) judge_command: Option<String>,
+    /// Testcase directory (`-d`).
+    ///
+    /// A relative path is resolved against the `<name>` directory for `[problems.<name>]`,
+    /// or against the directory of `cpt.toml` otherwise.
+    pub(crate) directory: Option<PathBuf>,
+    pub(crate) timelimit_ms: Option<u64>,
+    pub(crate) memorylimit_mb: Option<u64>,
+    pub(crate) outputlimit_mb: Option<u64>,
+    /// Factor applied to `timelimit_ms` (`--tl-scale`).
+    #[serde(default, deserialize_with = "deserialize_tl_scale")]
+    pub(crate) tl_scale: Option<f64>,
+    /// Input generator command (`-i`).
+    pub(crate) input_generator: Option<String>,
+    /// Output generator command (`-o`).
+    pub(crate) output_generator: Option<String>,
+    /// Input validator command (`--validator`).
+    pub(crate) validator: Option<String>,
+    /// Source file of the solution (`-s`), compiled before testing.
+    pub(crate) source: Option<PathBuf>,
+    /// Language profiles overriding the built-in ones.
+    #[serde(default)]
+    pub(crate) languages: BTreeMap<String, crate::language::Profile>,
+}
+
+/// Result of [`Problem::compile`].
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Compiled {
+    /// Command running the build artifact, which precedes `command` of the config.
+    pub(crate) command: Option<String>,
+    /// `tl_scale` of the language profile of the source.
+    pub(crate) tl_scale: Option<f64>,
+    /// Whether the source failed to compile.
+    pub(crate) error: bool,
+}
+impl Problem {
+    /// Fills the fields missing in `self` with the ones of `base`.
+    fn or(self, base: Problem) -> Problem {
+        Problem {
+            command: self.command.or(base.command),
+            judge: self.judge.or(base.judge),
+            judge_command: self.judge_command.or(base.judge_command),
+            directory: self.directory.or(base.directory),
+            timelimit_ms: self.timelimit_ms.or(base.timelimit_ms),
+            memorylimit_mb: self.memorylimit_mb.or(base.memorylimit_mb),
+            outputlimit_mb: self.outputlimit_mb.or(base.outputlimit_mb),
+            tl_scale: self.tl_scale.or(base.tl_scale),
+            input_generator: self.input_generator.or(base.input_generator),
+            output_generator: self.output_generator.or(base.output_generator),
+            validator: self.validator.or(base.validator),
+            source: self.source.or(base.source),
+            languages: base.languages.into_iter().chain(self.languages).collect(),
+        }
+    }
+
+    fn resolve_directory(mut self, basedir: &Path) -> Problem {
+        self.directory = self.directory.map(|dir| basedir.join(dir));
+        self
+    }
+
+    /// Compiles `source` (`-s` or the config) if any, and returns the command to run the build artifact.
+    ///
+    /// `command` given by `-c` takes precedence and skips the compilation.
+    /// A compile error is returned as `Compiled::error` instead of failing.
+    pub(crate) fn compile(
+        &self,
+        source: Option<&PathBuf>,
+        command: Option<&String>,
+    ) -> Result<Compiled, crate::language::Error> {
+        use crate::language::{build, run_command, tl_scale, Error};
+
+        let mut compiled = Compiled::default();
+        if command.is_some() {
+            return Ok(compiled);
+        }
+        if let Some(source) = source.or(self.source.as_ref()) {
+            compiled.command = Some(match build(source, &self.languages) {
+                Err(Error::Compilation(_)) => {
+                    compiled.error = true;
+                    run_command(source, &self.languages)?
+                }
+                result => result?,
+            });
+            compiled.tl_scale = tl_scale(source, &self.languages);
+        }
+        Ok(compiled)
+    }
+
+    /// Factor applied to the time limit: the product of `tl_scale`,
+    /// the one of the language (`language_tl_scale`) and the one of this machine.
+    pub(crate) fn time_scale(&self, language_tl_scale: Option<f64>) -> f64 {
+        self.tl_scale.unwrap_or(1.0)
+            * language_tl_scale.unwrap_or(1.0)
+            * crate::calibration::machine_factor().unwrap_or(1.0)
+    }
+
+    /// Resource limits, taking the command-line options in preference to this config.
+    ///
+    /// The time limit is the effective one scaled by [`Problem::time_scale`],
+    /// with `language_tl_scale` (`Compiled::tl_scale`) if the solution is compiled.
+    pub(crate) fn limits(
+        &self,
+        language_tl_scale: Option<f64>,
+        timelimit_ms: Option<u64>,
+        outputlimit_mb: Option<u64>,
+        memorylimit_mb: Option<u64>,
+    ) -> crate::judge::Limits {
+        let timelimit_ms = timelimit_ms.or(self.timelimit_ms).unwrap_or(10000);
+        let scale = self.time_scale(language_tl_scale);
+        let time_ms = (timelimit_ms as f64 * scale).round() as u64;
+        if time_ms != timelimit_ms {
+            log::info!(
+                "[Limits] Time limit: {}ms -> {}ms (x{:.2})",
+                timelimit_ms,
+                time_ms,
+                scale
+            );
+        }
+        crate::judge::Limits {
+            time_ms,
+            output_bytes: outputlimit_mb.or(self.outputlimit_mb).map(|mb| mb << 20),
+            memory_kb: memorylimit_mb.or(self.memorylimit_mb).map(|mb| mb << 10),
+        }
+    }
+}
+
+/// Checks a factor applied to the time limit, which must be positive and finite.
+///
+/// Otherwise (e.g. 0 or NaN), every case would exceed the time limit.
+pub(crate) fn check_tl_scale(scale: f64) -> Result<f64, String> {
+    if scale.is_finite() && scale > 0.0 {
+        Ok(scale)
+    } else {
+        Err(format!("`{}` is not a positive finite number.", scale))
+    }
+}
+
+/// Parses `--tl-scale`.
+pub(crate) fn parse_tl_scale(s: &str) -> Result<f64, String> {
+    check_tl_scale(s.parse::<f64>().map_err(|e| e.to_string())?)
+}
+
+/// Deserializes `tl_scale` of the config or a language profile, see [`check_tl_scale`].
+pub(crate) fn deserialize_tl_scale<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+
+    Option::<f64>::deserialize(deserializer)?
+        .map(check_tl_scale)
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+/// Contents of `cpt.toml`.
+///
+/// ```toml
+/// timelimit_ms = 2000
+/// directory = "test"
+///
+/// [problems.a]
+/// command = "./a.out"
+///
+/// [problems.b]
+/// command = "./a.out"
+/// judge = "special"
+/// judge_command = "./judge"
+///
+/// [languages.pypy]
+/// extensions = ["py"]
+/// run = "pypy3 {source}"
+/// tl_scale = 2.0
+/// ```
+///
+/// Top-level keys apply to every problem. `[problems.<name>]` applies
+/// when the current directory is (under) the `<name>` directory next to `cpt.toml`.
+#[derive(Debug, Default)]
+struct File {
+    defaults: Problem,
+    problems: BTreeMap<String, Problem>,
+}
+impl File {
+    /// Parses `content`, rejecting unknown keys (which `#[serde(flatten)]` would not).
+    fn parse(content: &str) -> Result<File, toml::de::Error> {
+        let mut table: toml::Table = toml::from_str(content)?;
+        let problems = match table.remove("problems") {
+            Some(problems) => problems.try_into()?,
+            None => BTreeMap::new(),
+        };
+        Ok(File {
+            defaults: toml::Value::Table(table).try_into()?,
+            problems,
+        })
+    }
+}
+
+/// Loads the config for the current directory from the nearest `cpt.toml`
+/// in the current or parent directories.
+///
+/// Returns the empty config if no file is found.
+pub(crate) fn load() -> Result<Problem, Error> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    match cwd
+        .ancestors()
+        .find(|dir| dir.join(CONFIG_FILENAME).is_file())
+    {
+        Some(root) => load_from(root, &cwd),
+        None => Ok(Problem::default()),
+    }
+}
+
+fn load_from(root: &Path, cwd: &Path) -> Result<Problem, Error> {
+    let path = root.join(CONFIG_FILENAME);
+    let content = cpt_stdx::fs::read(&path).map_err(|e| Error::Read(path.to_owned(), e))?;
+    let File {
+        defaults,
+        mut problems,
+    } = File::parse(&content).map_err(|e| Error::Parse(path.to_owned(), e))?;
+    log::debug!("[Config] Loaded `{}`", path.display());
+
+    let problem_name = cwd
+        .strip_prefix(root)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map(|component| component.as_os_str().to_string_lossy().into_owned());
+    Ok(
+        match problem_name.and_then(|name| problems.remove_entry(&name)) {
+            Some((name, problem)) => problem.or(defaults).resolve_directory(&root.join(name)),
+            None => defaults.resolve_directory(root),
+        },
+    )
+}
@@ -0,0 +1,82 @@
+use std::path::Path;
+
+/// Verdict of each input, one `<name>\t<verdict>` per line,
+/// followed by `\t<termination>` (e.g. `SIGSEGV`) for RE.
+const MANIFEST_FILENAME: &str = "corpus.tsv";
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Cannot read the corpus.")]
+    Read(#[source] cpt_stdx::fs::Error),
+    #[error("Cannot write to the corpus.")]
+    Write(#[source] cpt_stdx::fs::Error),
+}
+
+/// FNV-1a, which is stable across builds unlike `std::hash`.
+fn hash(content: &[u8]) -> u64 {
+    content.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Saves the input (and the expected output if any) of `case` to the corpus `dir`
+/// under the name of its content hash, with its judgement.
+///
+/// Returns `false` without saving if the same input is already in the corpus.
+pub(crate) fn record(
+    dir: &Path,
+    case: &crate::testcase::Testcase,
+    judgement: &crate::judge::Judgement,
+) -> Result<bool, Error> {
+    use std::io::Write;
+
+    use cpt_stdx::fs;
+
+    // Inputs are not necessarily UTF-8.
+    let input = std::fs::read(&case.input)
+        .map_err(|_| Error::Read(fs::Error::Read(case.input.to_owned())))?;
+    let name = format!("{:016x}", hash(&input));
+    let input_path = dir.join(name.to_owned() + ".in");
+    if input_path.exists() {
+        return Ok(false);
+    }
+    std::fs::create_dir_all(dir).map_err(|_| Error::Write(fs::Error::CreateDir(dir.to_owned())))?;
+    fs::copy(&case.input, &input_path).map_err(Error::Write)?;
+    if let Some(output) = &case.output {
+        fs::copy(output, dir.join(name.to_owned() + ".out")).map_err(Error::Write)?;
+    }
+    let mut line = format!("{}\t{}", name, judgement.verdict);
+    if let (crate::judge::Verdict::Re, Some(termination)) =
+        (judgement.verdict, judgement.termination)
+    {
+        line += &format!("\t{}", termination);
+    }
+    let manifest = dir.join(MANIFEST_FILENAME);
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&manifest)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|_| Error::Write(fs::Error::Write(manifest)))?;
+    log::debug!("[Corpus] Saved {} ({})", name, judgement.verdict);
+    Ok(true)
+}
+
+/// Recorded verdicts of the corpus `dir` by case name.
+pub(crate) fn load_verdicts(
+    dir: &Path,
+) -> Result<std::collections::BTreeMap<String, crate::judge::Verdict>, Error> {
+    let manifest = dir.join(MANIFEST_FILENAME);
+    if !manifest.exists() {
+        return Ok(Default::default());
+    }
+    let content = cpt_stdx::fs::read(&manifest).map_err(Error::Read)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let (name, verdict) = (fields.next()?, fields.next()?);
+            Some((name.to_owned(), verdict.parse().ok()?))
+        })
+        .collect())
+}
@@ -1,52 +1,222 @@
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum Error {
     #[error("Failed to execute input_generator.")]
     InputGeneration(#[source] cpt_stdx::process::Error),
     #[error("Failed to execute output_generator.")]
     OutputGeneration(#[source] cpt_stdx::process::Error),
+    #[error("Cannot write `{0}`.")]
+    Write(PathBuf, #[source] std::io::Error),
+}
+
+/// Parameter schedule of the input generator, e.g. `n=1..10,100,1000`.
+///
+/// Ranges are inclusive. The `k`-th trial uses the `k`-th value, and the last value
+/// after the schedule runs out, so that small sizes are tried first.
+/// A named parameter is passed as `-<name> <value>`, and an unnamed one (`1..10`) as `<value>`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Param {
+    name: Option<String>,
+    items: Vec<Item>,
+}
+
+/// Item of a parameter schedule, kept unexpanded so that a long range costs nothing.
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    /// Inclusive range `first..last`, with `first <= last`.
+    Range(i64, i64),
+    Value(String),
+}
+impl Item {
+    fn len(&self) -> u64 {
+        match self {
+            Item::Range(first, last) => last.abs_diff(*first).saturating_add(1),
+            Item::Value(_) => 1,
+        }
+    }
+
+    fn nth(&self, n: u64) -> String {
+        match self {
+            Item::Range(first, _) => (*first as i128 + n as i128).to_string(),
+            Item::Value(value) => value.to_owned(),
+        }
+    }
+}
+
+impl std::str::FromStr for Param {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, schedule) = match s.split_once('=') {
+            Some((name, schedule)) => (Some(name.to_owned()), schedule),
+            None => (None, s),
+        };
+        let mut items = Vec::new();
+        for item in schedule.split(',').filter(|item| !item.is_empty()) {
+            match item.split_once("..") {
+                Some((first, last)) => {
+                    let parse = |bound: &str| {
+                        bound
+                            .parse::<i64>()
+                            .map_err(|_| format!("`{}` in `{}` is not an integer.", bound, item))
+                    };
+                    let (first, last) = (parse(first)?, parse(last)?);
+                    // An empty range (e.g. `10..1`) has no value.
+                    if first <= last {
+                        items.push(Item::Range(first, last));
+                    }
+                }
+                None => items.push(Item::Value(item.to_owned())),
+            }
+        }
+        if items.is_empty() {
+            return Err(format!("`{}` has no value.", s));
+        }
+        Ok(Param { name, items })
+    }
+}
+impl Param {
+    fn value(&self, trial: usize) -> String {
+        let mut index = trial.max(1) as u64 - 1;
+        for item in &self.items {
+            if index < item.len() {
+                return item.nth(index);
+            }
+            index -= item.len();
+        }
+        let last = self.items.last().unwrap();
+        last.nth(last.len() - 1)
+    }
+}
+
+/// Arguments of the input generator for the `trial`-th trial (1-indexed).
+pub(crate) fn param_args(params: &[Param], trial: usize) -> Vec<String> {
+    params
+        .iter()
+        .flat_map(|param| {
+            param
+                .name
+                .as_ref()
+                .map(|name| format!("-{}", name))
+                .into_iter()
+                .chain([param.value(trial)])
+        })
+        .collect()
+}
+
+/// Values of the named parameters for the `trial`-th trial.
+pub(crate) fn param_values(
+    params: &[Param],
+    trial: usize,
+) -> std::collections::BTreeMap<String, String> {
+    params
+        .iter()
+        .filter_map(|param| Some((param.name.to_owned()?, param.value(trial))))
+        .collect()
+}
+
+/// Human-readable parameters for the `trial`-th trial, e.g. `n=10 m=3`.
+pub(crate) fn describe_params(params: &[Param], trial: usize) -> String {
+    params
+        .iter()
+        .map(|param| match &param.name {
+            Some(name) => format!("{}={}", name, param.value(trial)),
+            None => param.value(trial),
+        })
+        .join(" ")
 }
 
 pub(crate) fn generate(
     testcase: &crate::testcase::Testcase,
     input_generator_command: &str,
+    input_generator_args: &[String],
     output_generator_command: &Option<String>,
     timelimit_ms: u64,
 ) -> Result<crate::testcase::Testcase, Error> {
     use std::process::Stdio;
 
-    use cpt_stdx::fs;
-    use cpt_stdx::process::{Command, IoRedirection};
+    use cpt_stdx::process::Command;
 
     log::info!("[Generator][{}] Start", testcase.casename);
+    let mut input_generator = Command::from(input_generator_command);
+    input_generator.args.extend_from_slice(input_generator_args);
+    exec_to_file(
+        &input_generator,
+        Stdio::null(),
+        &testcase.input,
+        timelimit_ms,
+        Error::InputGeneration,
+    )?;
+    let case = generate_output(testcase, output_generator_command, timelimit_ms)?;
+    log::info!("[Generator][{}] End", testcase.casename);
+    Ok(case)
+}
+
+/// Generates the expected output of the existing input of `testcase`, if the output generator is given.
+pub(crate) fn generate_output(
+    testcase: &crate::testcase::Testcase,
+    output_generator_command: &Option<String>,
+    timelimit_ms: u64,
+) -> Result<crate::testcase::Testcase, Error> {
+    use std::process::Stdio;
+
+    use cpt_stdx::fs;
+    use cpt_stdx::process::Command;
+
     let mut case = testcase.to_owned();
-    Command::from(input_generator_command)
+    if let Some(output_generator_command) = output_generator_command {
+        exec_to_file(
+            &Command::from(output_generator_command),
+            Stdio::from(fs::open(&testcase.input).unwrap()),
+            testcase.output.as_ref().unwrap(),
+            timelimit_ms,
+            Error::OutputGeneration,
+        )?;
+    } else {
+        case.output = None;
+    }
+    Ok(case)
+}
+
+/// Runs `command` with its stdout written to `path`, which is replaced only if it succeeds.
+///
+/// The output goes to a temporary file in the same directory first, so that a crash
+/// or a timeout neither leaves a truncated file nor destroys the existing one.
+fn exec_to_file(
+    command: &cpt_stdx::process::Command,
+    stdin: std::process::Stdio,
+    path: &Path,
+    timelimit_ms: u64,
+    failed: fn(cpt_stdx::process::Error) -> Error,
+) -> Result<(), Error> {
+    use std::process::Stdio;
+
+    use cpt_stdx::process::IoRedirection;
+
+    let write_error = |e| Error::Write(path.to_owned(), e);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(dir).map_err(write_error)?;
+    let file = tempfile::Builder::new()
+        .prefix(".cpt-")
+        .tempfile_in(dir)
+        .map_err(write_error)?;
+    command
         .exec(
             IoRedirection {
-                stdin: Stdio::null(),
-                stdout: Stdio::from(fs::create(&testcase.input, true).unwrap()),
+                stdin,
+                stdout: Stdio::from(file.as_file().try_clone().map_err(write_error)?),
                 stderr: Stdio::piped(),
             },
             timelimit_ms,
             true,
         )
-        .map_err(Error::InputGeneration)?;
-    if let Some(output_generator_command) = output_generator_command {
-        Command::from(output_generator_command)
-            .exec(
-                IoRedirection {
-                    stdin: Stdio::from(fs::open(&testcase.input).unwrap()),
-                    stdout: Stdio::from(
-                        fs::create(testcase.output.as_ref().unwrap(), true).unwrap(),
-                    ),
-                    stderr: Stdio::piped(),
-                },
-                timelimit_ms,
-                true,
-            )
-            .map_err(Error::OutputGeneration)?;
-    } else {
-        case.output = None;
-    }
-    log::info!("[Generator][{}] End", testcase.casename);
-    Ok(case)
+        .map_err(failed)?;
+    file.persist(path).map_err(|e| write_error(e.error))?;
+    Ok(())
 }
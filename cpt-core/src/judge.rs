@@ -2,11 +2,56 @@ pub mod batch;
 pub mod reactive;
 pub mod special;
 
-#[derive(Debug, Clone, strum::Display, strum::EnumCount, strum::EnumIter, strum::EnumIs)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumCount,
+    strum::EnumIter,
+    strum::EnumIs,
+    strum::EnumString,
+    clap::ValueEnum,
+)]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 pub(crate) enum Verdict {
+    #[value(skip)]
     Ac,
     Wa,
     Re,
     Tle,
+    Ole,
+    Mle,
+    /// The solution failed to compile, so the case was not run.
+    #[value(skip)]
+    Ce,
+}
+
+/// Result of judging a case.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Judgement {
+    pub(crate) verdict: Verdict,
+    /// Elapsed time of the program.
+    pub(crate) elapsed_ms: u64,
+    /// CPU time of the program, if the platform reports it.
+    pub(crate) cpu_ms: Option<u64>,
+    /// How the program terminated (e.g. `SIGSEGV` for RE), or `None` if it was killed on TLE.
+    pub(crate) termination: Option<cpt_stdx::process::Termination>,
+}
+
+/// Resource limits of the program under judge.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    pub(crate) time_ms: u64,
+    /// Limit of each of stdout and stderr.
+    pub(crate) output_bytes: Option<u64>,
+    /// Limit of the peak resident set size.
+    pub(crate) memory_kb: Option<u64>,
+}
+impl Limits {
+    pub(crate) fn is_memory_exceeded(&self, detail: &cpt_stdx::process::StatusDetail) -> bool {
+        matches!((self.memory_kb, detail.memory_kb), (Some(limit), Some(used)) if used > limit)
+    }
 }
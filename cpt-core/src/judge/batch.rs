@@ -36,51 +36,50 @@ impl JudgeFiles {
 pub(crate) fn judge(
     program_command: &str,
     testcase: crate::testcase::Testcase,
-    timelimit_ms: u64,
+    limits: &crate::judge::Limits,
     dir: &Path,
-) -> Result<crate::judge::Verdict, Error> {
+) -> Result<crate::judge::Judgement, Error> {
     use std::process::Stdio;
 
-    use similar::{ChangeTag, TextDiff};
-
     use cpt_stdx::fs;
-    use cpt_stdx::process::{Command, IoRedirection, Status, StatusSummary};
+    use cpt_stdx::process::{
+        Capture, CaptureRedirection, Captured, Command, Status, StatusSummary,
+    };
 
-    use crate::judge::Verdict;
+    use crate::judge::{Judgement, Verdict};
 
     let judge_files = JudgeFiles::new(testcase, dir);
     log::info!("[Judge][{}] Start", judge_files.casename);
-    let Status { summary, detail } = Command::from(program_command)
-        .exec(
-            IoRedirection {
+    let Captured {
+        status: Status { summary, detail },
+        ..
+    } = Command::from(program_command)
+        .exec_capture(
+            CaptureRedirection {
                 stdin: Stdio::from(fs::open(judge_files.input).unwrap()),
-                stdout: Stdio::from(fs::create(&judge_files.actual, true).unwrap()),
-                stderr: Stdio::from(fs::create(&judge_files.debug, true).unwrap()),
+                stdout: Capture::File(fs::create(&judge_files.actual, true).unwrap()),
+                stderr: Capture::File(fs::create(&judge_files.debug, true).unwrap()),
+                limit_bytes: limits.output_bytes,
             },
-            timelimit_ms,
+            limits.time_ms,
             false,
         )
         .map_err(Error::Program)?;
-    Ok(match summary {
+    let verdict = match summary {
+        StatusSummary::Success | StatusSummary::Aborted if limits.is_memory_exceeded(&detail) => {
+            log::warn!(
+                "[Judge][{}] MLE ({}KB/{}KB)",
+                judge_files.casename,
+                detail.memory_kb.unwrap(),
+                limits.memory_kb.unwrap()
+            );
+            Verdict::Mle
+        }
         StatusSummary::Success => {
             if let Some(expect_path) = judge_files.expect {
                 let actual = fs::read(&judge_files.actual).unwrap();
                 let expect = fs::read(&expect_path).map_err(Error::ReadExpectation)?;
-                if actual == expect {
-                    log::info!("[Judge][{}] AC", judge_files.casename);
-                    Verdict::Ac
-                } else {
-                    let diff_lines = TextDiff::from_lines(&expect, &actual)
-                        .iter_all_changes()
-                        .map(|change| {
-                            let sign = match change.tag() {
-                                ChangeTag::Delete => "-",
-                                ChangeTag::Insert => "+",
-                                ChangeTag::Equal => " ",
-                            };
-                            format!("{} {}", sign, change.to_string().trim_end())
-                        })
-                        .collect_vec();
+                if let Some(diff_lines) = compare(&expect, &actual) {
                     log::warn!("[Judge][{}] WA", judge_files.casename);
                     diff_lines
                         .to_owned()
@@ -88,6 +87,9 @@ pub(crate) fn judge(
                         .for_each(|line| log::warn!("{}", line));
                     fs::write(judge_files.judge, diff_lines.into_iter().join("\n"), true).unwrap();
                     Verdict::Wa
+                } else {
+                    log::info!("[Judge][{}] AC", judge_files.casename);
+                    Verdict::Ac
                 }
             } else {
                 log::info!("[Judge][{}] AC", judge_files.casename);
@@ -95,7 +97,14 @@ pub(crate) fn judge(
             }
         }
         StatusSummary::Aborted => {
-            log::warn!("[Judge][{}] RE", judge_files.casename);
+            log::warn!(
+                "[Judge][{}] RE ({})",
+                judge_files.casename,
+                detail
+                    .termination()
+                    .map(|termination| termination.to_string())
+                    .unwrap_or_default()
+            );
             log::warn!("{}", fs::read(&judge_files.debug).unwrap());
             Verdict::Re
         }
@@ -104,9 +113,47 @@ pub(crate) fn judge(
                 "[Judge][{}] TLE ({}ms/{}ms)",
                 judge_files.casename,
                 detail.elapsed_ms,
-                timelimit_ms
+                limits.time_ms
             );
             Verdict::Tle
         }
+        StatusSummary::OutputLimitExceeded => {
+            log::warn!(
+                "[Judge][{}] OLE ({}bytes)",
+                judge_files.casename,
+                limits.output_bytes.unwrap()
+            );
+            Verdict::Ole
+        }
+    };
+    Ok(Judgement {
+        verdict,
+        elapsed_ms: detail.elapsed_ms,
+        cpu_ms: detail.cpu_ms,
+        termination: detail.termination(),
     })
 }
+
+/// Compares the program output with the expected output exactly.
+///
+/// Returns `None` if they match, otherwise the line diff (`-` for expected, `+` for actual).
+pub(crate) fn compare(expect: &str, actual: &str) -> Option<Vec<String>> {
+    use similar::{ChangeTag, TextDiff};
+
+    if actual == expect {
+        return None;
+    }
+    Some(
+        TextDiff::from_lines(expect, actual)
+            .iter_all_changes()
+            .map(|change| {
+                let sign = match change.tag() {
+                    ChangeTag::Delete => "-",
+                    ChangeTag::Insert => "+",
+                    ChangeTag::Equal => " ",
+                };
+                format!("{} {}", sign, change.to_string().trim_end())
+            })
+            .collect_vec(),
+    )
+}
@@ -32,32 +32,57 @@ pub(crate) fn judge(
     program_command: &str,
     judge_command: &str,
     testcase: crate::testcase::Testcase,
-    timelimit_ms: u64,
+    limits: &crate::judge::Limits,
     dir: &Path,
-) -> Result<crate::judge::Verdict, Error> {
+) -> Result<crate::judge::Judgement, Error> {
     use cpt_stdx::fs;
-    use cpt_stdx::process::{Status, StatusSummary};
+    use cpt_stdx::process::{Status, StatusSummary, Termination};
 
-    use crate::judge::Verdict;
+    use crate::judge::{Judgement, Verdict};
 
     let judge_files = JudgeFiles::new(testcase, dir);
     log::info!("[Judge][{}] Start", judge_files.casename);
 
-    let Status { summary, detail } = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap()
-        .block_on(async {
-            exec_timeout(
-                program_command,
-                judge_command,
-                judge_files.to_owned(),
-                timelimit_ms,
-            )
-            .await
-        })?;
+    let (Status { summary, detail }, program) = cpt_stdx::process::runtime().block_on(async {
+        exec_timeout(
+            program_command,
+            judge_command,
+            judge_files.to_owned(),
+            limits.time_ms,
+            limits.output_bytes,
+        )
+        .await
+    })?;
+    let termination = program
+        .as_ref()
+        .and_then(|program| program.detail.termination());
 
-    Ok(match summary {
+    let verdict = match summary {
+        StatusSummary::Success | StatusSummary::Aborted
+            if program
+                .as_ref()
+                .is_some_and(|program| limits.is_memory_exceeded(&program.detail)) =>
+        {
+            log::warn!(
+                "[Judge][{}] MLE ({}KB/{}KB)",
+                judge_files.casename,
+                program.as_ref().unwrap().detail.memory_kb.unwrap(),
+                limits.memory_kb.unwrap()
+            );
+            Verdict::Mle
+        }
+        // The judge usually fails as well when the program crashes, so the crash takes precedence.
+        StatusSummary::Success | StatusSummary::Aborted
+            if termination.is_some_and(|termination| termination != Termination::Exit(0)) =>
+        {
+            log::warn!(
+                "[Judge][{}] RE ({})",
+                judge_files.casename,
+                termination.unwrap()
+            );
+            log::warn!("{}", fs::read(&judge_files.debug).unwrap());
+            Verdict::Re
+        }
         StatusSummary::Success => {
             log::info!("[Judge][{}] AC", judge_files.casename);
             Verdict::Ac
@@ -65,43 +90,83 @@ pub(crate) fn judge(
         StatusSummary::Aborted => {
             log::warn!("[Judge][{}] WA", judge_files.casename);
             log::warn!("{}", fs::read(&judge_files.debug).unwrap());
-            Verdict::Re
+            Verdict::Wa
         }
         StatusSummary::Timeout => {
             log::warn!(
                 "[Judge][{}] TLE ({}ms/{}ms)",
                 judge_files.casename,
                 detail.elapsed_ms,
-                timelimit_ms
+                limits.time_ms
             );
             Verdict::Tle
         }
+        StatusSummary::OutputLimitExceeded => {
+            log::warn!(
+                "[Judge][{}] OLE ({}bytes, {}ms)",
+                judge_files.casename,
+                limits.output_bytes.unwrap(),
+                detail.elapsed_ms
+            );
+            Verdict::Ole
+        }
+    };
+    Ok(Judgement {
+        verdict,
+        elapsed_ms: detail.elapsed_ms,
+        cpu_ms: program.and_then(|program| program.detail.cpu_ms),
+        termination,
     })
 }
 
+/// How long the program is waited for after the judge ends.
+const PROGRAM_GRACE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Runs the interaction, and returns the status of the judge
+/// with the status of the program (`None` if it did not end).
 async fn exec_timeout(
     program_command: &str,
     judge_command: &str,
     judge_files: JudgeFiles,
     timelimit_ms: u64,
-) -> Result<cpt_stdx::process::Status, Error> {
+    outputlimit_bytes: Option<u64>,
+) -> Result<(cpt_stdx::process::Status, Option<cpt_stdx::process::Status>), Error> {
     use std::process::Stdio;
+    use std::sync::Arc;
 
-    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::sync::Notify;
 
     use cpt_stdx::fs;
     use cpt_stdx::process::{Command, IoRedirection, Status, StatusDetail, StatusSummary};
 
+    let start = std::time::Instant::now();
     let result = tokio::time::timeout(
         tokio::time::Duration::from_millis(timelimit_ms) * 2,
         async move {
+            let debug = fs::create(&judge_files.debug, true).unwrap();
             let mut program = Command::from(program_command)
                 .spawn(IoRedirection {
                     stdin: Stdio::piped(),
                     stdout: Stdio::piped(),
-                    stderr: Stdio::from(fs::create(&judge_files.debug, true).unwrap()),
+                    stderr: match outputlimit_bytes {
+                        Some(_) => Stdio::piped(),
+                        None => Stdio::from(debug.try_clone().unwrap()),
+                    },
                 })
                 .map_err(Error::Program)?;
+            // Notified when the program output (to the judge or to stderr) exceeds the limit.
+            let exceeded = Arc::new(Notify::new());
+            if let (Some(limit), Some(mut program_stderr)) = (outputlimit_bytes, program.stderr.take()) {
+                let exceeded = exceeded.clone();
+                tokio::spawn(async move {
+                    let mut debug = tokio::fs::File::from_std(debug);
+                    let _ = tokio::io::copy(&mut (&mut program_stderr).take(limit), &mut debug).await;
+                    if program_stderr.read(&mut [0u8]).await.is_ok_and(|len| len > 0) {
+                        exceeded.notify_one();
+                    }
+                });
+            }
             let mut judge_command = Command::from(judge_command);
             judge_command.args.push(format!("{}",judge_files.input.display()));
             let mut judge = judge_command
@@ -112,19 +177,27 @@ async fn exec_timeout(
                 })
                 .map_err(Error::Judge)?;
                 let mut program_stdin = program.stdin.take().unwrap();
-                let mut program_stdout = BufReader::new(program.stdout.take().unwrap()).lines();
+                let mut program_stdout = program.stdout.take().unwrap();
                 let mut judge_stdin = judge.stdin.take().unwrap();
                 let mut judge_stdout = BufReader::new(judge.stdout.take().unwrap()).lines();
+            let interaction_exceeded = exceeded.clone();
             let interaction = async move {
+                let mut program_output_bytes = 0;
+                let mut buf = [0u8; 8192];
                 loop {
                     tokio::select! {
-                        program_line = program_stdout.next_line() => {
-                            if let Ok(Some(line)) = program_line {
-                                    judge_stdin.write_all(line.as_bytes()).await.map_err(Error::Communication)?;
-                                    judge_stdin.write_all(b"\n").await.map_err(Error::Communication)?;
+                        program_read = program_stdout.read(&mut buf) => {
+                            match program_read {
+                                Ok(len) if len > 0 => {
+                                    program_output_bytes += len as u64;
+                                    if outputlimit_bytes.is_some_and(|limit| program_output_bytes > limit) {
+                                        interaction_exceeded.notify_one();
+                                        break;
+                                    }
+                                    judge_stdin.write_all(&buf[..len]).await.map_err(Error::Communication)?;
                                     judge_stdin.flush().await.map_err(Error::Communication)?;
-                            } else {
-                                break;
+                                }
+                                _ => break,
                             }
                         }
                         judge_line = judge_stdout.next_line() => {
@@ -140,39 +213,88 @@ async fn exec_timeout(
                 }
                 Ok::<(), Error>(())
             };
-            tokio::spawn(interaction);
-            Ok(Status::from(judge.wait_with_output().await.unwrap()))
+            let interaction = tokio::spawn(interaction);
+            tokio::select! {
+                status = judge.wait() => {
+                    let elapsed_ms = start.elapsed().as_millis() as u64;
+                    let program_status = tokio::time::timeout(PROGRAM_GRACE, program.wait()).await.ok();
+                    // A program still running is killed, which ends the interaction.
+                    drop(program);
+                    match interaction.await.unwrap() {
+                        // Either side ended first, which its status tells.
+                        Err(Error::Communication(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+                        result => result?,
+                    }
+                    let Status { summary, detail } = status;
+                    Ok((Status { summary, detail: StatusDetail { elapsed_ms, ..detail } }, program_status))
+                },
+                _ = exceeded.notified() => Ok((Status {
+                    summary: StatusSummary::OutputLimitExceeded,
+                    detail: StatusDetail {
+                        stdout: "".into(),
+                        stderr: "".into(),
+                        elapsed_ms: start.elapsed().as_millis() as u64,
+                        memory_kb: None,
+                        cpu_ms: None,
+                        exit_code: None,
+                        signal: None,
+                    },
+                }, None)),
+            }
         },
     ).await;
     match result {
         Ok(Err(e)) => Err(e),
-        Ok(Ok(Status { summary, detail })) => match summary {
+        Ok(Ok((Status { summary, detail }, program_status))) => match summary {
             StatusSummary::Success => {
                 if detail.elapsed_ms <= timelimit_ms {
-                    Ok(Status {
-                        summary: StatusSummary::Success,
-                        detail,
-                    })
+                    Ok((
+                        Status {
+                            summary: StatusSummary::Success,
+                            detail,
+                        },
+                        program_status,
+                    ))
                 } else {
-                    Ok(Status {
-                        summary: StatusSummary::Timeout,
-                        detail,
-                    })
+                    Ok((
+                        Status {
+                            summary: StatusSummary::Timeout,
+                            detail,
+                        },
+                        program_status,
+                    ))
                 }
             }
-            StatusSummary::Aborted => Ok(Status {
-                summary: StatusSummary::Aborted,
-                detail,
-            }),
+            StatusSummary::Aborted => Ok((
+                Status {
+                    summary: StatusSummary::Aborted,
+                    detail,
+                },
+                program_status,
+            )),
+            StatusSummary::OutputLimitExceeded => Ok((
+                Status {
+                    summary: StatusSummary::OutputLimitExceeded,
+                    detail,
+                },
+                program_status,
+            )),
             _ => unreachable!(),
         },
-        Err(_) => Ok(Status {
-            summary: StatusSummary::Timeout,
-            detail: StatusDetail {
-                stdout: "".into(),
-                stderr: "".into(),
-                elapsed_ms: timelimit_ms * 2,
+        Err(_) => Ok((
+            Status {
+                summary: StatusSummary::Timeout,
+                detail: StatusDetail {
+                    stdout: "".into(),
+                    stderr: "".into(),
+                    elapsed_ms: timelimit_ms * 2,
+                    memory_kb: None,
+                    cpu_ms: None,
+                    exit_code: None,
+                    signal: None,
+                },
             },
-        }),
+            None,
+        )),
     }
 }
@@ -12,6 +12,7 @@ pub(crate) enum Error {
 struct JudgeFiles {
     casename: String,
     input: PathBuf,
+    answer: Option<PathBuf>,
     actual: PathBuf,
     debug: PathBuf,
     judge: PathBuf,
@@ -21,6 +22,7 @@ impl JudgeFiles {
         Self {
             casename: testcase.casename.to_owned(),
             input: testcase.input,
+            answer: testcase.output,
 
             actual: dir.join(testcase.casename.to_owned() + ".actual.txt"),
             debug: dir.join(testcase.casename.to_owned() + ".debug.txt"),
@@ -31,8 +33,10 @@ impl JudgeFiles {
 
 /// The special judge program should follow this command-line interface:
 /// ```text
-/// $ <judge_program> <input_file> <output_file>
+/// $ <judge_program> <input_file> <output_file> [<answer_file>]
 /// ```
+/// `<answer_file>` is the output of the testcase, given only if it is set.
+///
 /// AC:  The judge program ended (Regardless of return code).
 /// WA:  The judge program aborted or timeout.
 /// RE:  The main program aborted.
@@ -41,30 +45,45 @@ pub(crate) fn judge(
     program_command: &str,
     judge_command: &str,
     testcase: crate::testcase::Testcase,
-    timelimit_ms: u64,
+    limits: &crate::judge::Limits,
     dir: &Path,
-) -> Result<crate::judge::Verdict, Error> {
+) -> Result<crate::judge::Judgement, Error> {
     use std::process::Stdio;
 
     use cpt_stdx::fs;
-    use cpt_stdx::process::{Command, IoRedirection, Status, StatusSummary};
+    use cpt_stdx::process::{
+        Capture, CaptureRedirection, Captured, Command, IoRedirection, Status, StatusSummary,
+    };
 
-    use crate::judge::Verdict;
+    use crate::judge::{Judgement, Verdict};
 
     let judge_files = JudgeFiles::new(testcase, dir);
     log::info!("[Judge][{}] Start", judge_files.casename);
-    let Status { summary, detail } = Command::from(program_command)
-        .exec(
-            IoRedirection {
+    let Captured {
+        status: Status { summary, detail },
+        ..
+    } = Command::from(program_command)
+        .exec_capture(
+            CaptureRedirection {
                 stdin: Stdio::from(fs::open(&judge_files.input).unwrap()),
-                stdout: Stdio::from(fs::create(&judge_files.actual, true).unwrap()),
-                stderr: Stdio::from(fs::create(&judge_files.debug, true).unwrap()),
+                stdout: Capture::File(fs::create(&judge_files.actual, true).unwrap()),
+                stderr: Capture::File(fs::create(&judge_files.debug, true).unwrap()),
+                limit_bytes: limits.output_bytes,
             },
-            timelimit_ms,
+            limits.time_ms,
             false,
         )
         .map_err(Error::Program)?;
-    Ok(match summary {
+    let verdict = match summary {
+        StatusSummary::Success | StatusSummary::Aborted if limits.is_memory_exceeded(&detail) => {
+            log::warn!(
+                "[Judge][{}] MLE ({}KB/{}KB)",
+                judge_files.casename,
+                detail.memory_kb.unwrap(),
+                limits.memory_kb.unwrap()
+            );
+            Verdict::Mle
+        }
         StatusSummary::Success => {
             let mut command = Command::from(judge_command);
             command
@@ -73,6 +92,9 @@ pub(crate) fn judge(
             command
                 .args
                 .push(format!("{}", judge_files.actual.display()));
+            if let Some(answer) = &judge_files.answer {
+                command.args.push(format!("{}", answer.display()));
+            }
             let Status { summary, detail: _ } = command
                 .exec(
                     IoRedirection {
@@ -80,7 +102,7 @@ pub(crate) fn judge(
                         stdout: Stdio::from(fs::create(&judge_files.judge, true).unwrap()),
                         stderr: Stdio::from(fs::create(&judge_files.judge, true).unwrap()),
                     },
-                    timelimit_ms * 10,
+                    limits.time_ms * 10,
                     false,
                 )
                 .map_err(Error::Judge)?;
@@ -96,7 +118,14 @@ pub(crate) fn judge(
             }
         }
         StatusSummary::Aborted => {
-            log::warn!("[Judge][{}] RE", judge_files.casename);
+            log::warn!(
+                "[Judge][{}] RE ({})",
+                judge_files.casename,
+                detail
+                    .termination()
+                    .map(|termination| termination.to_string())
+                    .unwrap_or_default()
+            );
             log::warn!("{}", fs::read(&judge_files.debug).unwrap());
             Verdict::Re
         }
@@ -105,9 +134,23 @@ pub(crate) fn judge(
                 "[Judge][{}] TLE ({}ms/{}ms)",
                 judge_files.casename,
                 detail.elapsed_ms,
-                timelimit_ms
+                limits.time_ms
             );
             Verdict::Tle
         }
+        StatusSummary::OutputLimitExceeded => {
+            log::warn!(
+                "[Judge][{}] OLE ({}bytes)",
+                judge_files.casename,
+                limits.output_bytes.unwrap()
+            );
+            Verdict::Ole
+        }
+    };
+    Ok(Judgement {
+        verdict,
+        elapsed_ms: detail.elapsed_ms,
+        cpu_ms: detail.cpu_ms,
+        termination: detail.termination(),
     })
 }
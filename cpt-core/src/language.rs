@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("`{0}` is not found.")]
+    SourceNotFound(PathBuf),
+    #[error("No language profile for `{0}`.")]
+    UnknownLanguage(PathBuf),
+    #[error("Compile error in `{0}`.")]
+    Compilation(PathBuf),
+    #[error("Failed to execute compiler.")]
+    Compiler(#[source] cpt_stdx::process::Error),
+}
+
+/// How to build and run a source file of a language.
+///
+/// Commands may contain the placeholders below:
+/// - `{source}`: Path of the source file
+/// - `{binary}`: Path of the build artifact
+/// - `{dir}`: Directory of the source file
+/// - `{stem}`: File name of the source without the extension
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct Profile {
+    pub(crate) extensions: Vec<String>,
+    /// Compile command. `None` for interpreted languages.
+    pub(crate) compile: Option<String>,
+    pub(crate) run: String,
+    /// Build artifact, which is compared with the source to skip compilation.
+    #[serde(default = "default_binary")]
+    pub(crate) binary: String,
+    /// Timeout of the compile command, [`DEFAULT_COMPILE_TIMEOUT_MS`] if not given.
+    pub(crate) compile_timeout_ms: Option<u64>,
+    /// Factor applied to the time limit when the solution is built from this language.
+    #[serde(default, deserialize_with = "crate::config::deserialize_tl_scale")]
+    pub(crate) tl_scale: Option<f64>,
+}
+
+const DEFAULT_COMPILE_TIMEOUT_MS: u64 = 60000;
+
+fn default_binary() -> String {
+    "{dir}/{stem}.exe".into()
+}
+
+struct Builtin {
+    extensions: &'static [&'static str],
+    compile: Option<&'static str>,
+    run: &'static str,
+    binary: &'static str,
+}
+
+/// Built-in profiles (C++, C, Rust, Go, Java, Python).
+const BUILTIN_PROFILES: &[Builtin] = &[
+    Builtin {
+        extensions: &["cpp", "cc", "cxx"],
+        compile: Some("g++ -std=gnu++20 -O2 -o {binary} {source}"),
+        run: "{binary}",
+        binary: "{dir}/{stem}.exe",
+    },
+    Builtin {
+        extensions: &["c"],
+        compile: Some("gcc -std=gnu17 -O2 -o {binary} {source} -lm"),
+        run: "{binary}",
+        binary: "{dir}/{stem}.exe",
+    },
+    Builtin {
+        extensions: &["rs"],
+        compile: Some("rustc --edition 2021 -O -o {binary} {source}"),
+        run: "{binary}",
+        binary: "{dir}/{stem}.exe",
+    },
+    Builtin {
+        extensions: &["go"],
+        compile: Some("go build -o {binary} {source}"),
+        run: "{binary}",
+        binary: "{dir}/{stem}.exe",
+    },
+    Builtin {
+        extensions: &["java"],
+        compile: Some("javac -d {dir} {source}"),
+        run: "java -cp {dir} {stem}",
+        binary: "{dir}/{stem}.class",
+    },
+    Builtin {
+        extensions: &["py"],
+        compile: None,
+        run: "python3 {source}",
+        binary: "{source}",
+    },
+];
+
+/// Finds the profile for the extension of `source`.
+///
+/// Profiles in `cpt.toml` (`[languages.<name>]`) take precedence over the built-in ones.
+fn find_profile(source: &Path, custom: &BTreeMap<String, Profile>) -> Option<Profile> {
+    let extension = cpt_stdx::path::get_extension(source);
+    custom
+        .values()
+        .find(|profile| profile.extensions.contains(&extension))
+        .cloned()
+        .or_else(|| {
+            BUILTIN_PROFILES
+                .iter()
+                .find(|builtin| builtin.extensions.contains(&extension.as_str()))
+                .map(|builtin| Profile {
+                    extensions: builtin
+                        .extensions
+                        .iter()
+                        .map(|ext| ext.to_string())
+                        .collect(),
+                    compile: builtin.compile.map(Into::into),
+                    run: builtin.run.into(),
+                    binary: builtin.binary.into(),
+                    compile_timeout_ms: None,
+                    tl_scale: None,
+                })
+        })
+}
+
+/// Factor of the time limit of the profile for `source`, if given.
+pub(crate) fn tl_scale(source: &Path, custom: &BTreeMap<String, Profile>) -> Option<f64> {
+    find_profile(source, custom).and_then(|profile| profile.tl_scale)
+}
+
+fn expand(template: &str, source: &Path, binary: &str) -> String {
+    use cpt_stdx::path::{get_basedir, get_filestem};
+
+    let dir = get_basedir(source);
+    let dir = if dir.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        dir
+    };
+    template
+        .replace("{binary}", binary)
+        .replace("{source}", &source.display().to_string())
+        .replace("{dir}", &dir.display().to_string())
+        .replace("{stem}", &get_filestem(source))
+}
+
+/// Command to run the build artifact of `source`.
+pub(crate) fn run_command(
+    source: &Path,
+    custom: &BTreeMap<String, Profile>,
+) -> Result<String, Error> {
+    let profile =
+        find_profile(source, custom).ok_or_else(|| Error::UnknownLanguage(source.to_owned()))?;
+    let binary = expand(&profile.binary, source, "");
+    Ok(expand(&profile.run, source, &binary))
+}
+
+/// Compiles `source` if its build artifact is missing or older than the source,
+/// and returns the command to run it.
+///
+/// Compile errors are logged, and reported as `Error::Compilation`.
+pub(crate) fn build(source: &Path, custom: &BTreeMap<String, Profile>) -> Result<String, Error> {
+    use std::process::Stdio;
+
+    use cpt_stdx::process::{Command, IoRedirection, Status, StatusSummary};
+
+    if !source.is_file() {
+        return Err(Error::SourceNotFound(source.to_owned()));
+    }
+    let profile =
+        find_profile(source, custom).ok_or_else(|| Error::UnknownLanguage(source.to_owned()))?;
+    let binary = expand(&profile.binary, source, "");
+    let run_command = run_command(source, custom)?;
+
+    let Some(compile) = &profile.compile else {
+        return Ok(run_command);
+    };
+    let modified = |path: &Path| {
+        std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+    };
+    if let (Some(source_time), Some(binary_time)) = (modified(source), modified(Path::new(&binary)))
+    {
+        if binary_time >= source_time {
+            log::info!("[Compile][{}] Up to date", source.display());
+            return Ok(run_command);
+        }
+    }
+
+    log::info!("[Compile][{}] Start", source.display());
+    let compile_command = expand(compile, source, &binary);
+    log::debug!("[Compile] $ {}", compile_command);
+    let Status { summary, detail } = Command::from(&compile_command)
+        .exec(
+            IoRedirection {
+                stdin: Stdio::null(),
+                stdout: Stdio::piped(),
+                stderr: Stdio::piped(),
+            },
+            profile
+                .compile_timeout_ms
+                .unwrap_or(DEFAULT_COMPILE_TIMEOUT_MS),
+            false,
+        )
+        .map_err(Error::Compiler)?;
+    match summary {
+        StatusSummary::Success => {
+            log::info!("[Compile][{}] End", source.display());
+            Ok(run_command)
+        }
+        StatusSummary::Timeout => {
+            log::warn!(
+                "[Compile][{}] CE (Timeout: {}ms)",
+                source.display(),
+                detail.elapsed_ms
+            );
+            Err(Error::Compilation(source.to_owned()))
+        }
+        _ => {
+            log::warn!("[Compile][{}] CE", source.display());
+            log::warn!("{}{}", detail.stdout, detail.stderr);
+            Err(Error::Compilation(source.to_owned()))
+        }
+    }
+}
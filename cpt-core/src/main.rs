@@ -1,7 +1,15 @@
+mod calibration;
 mod commands;
+mod compare;
+mod config;
+mod corpus;
 mod generator;
 mod judge;
+mod language;
+mod shrink;
+mod spec;
 mod testcase;
+mod validator;
 
 use clap::Parser;
 use clap_verbosity_flag::{InfoLevel, Verbosity};
@@ -35,6 +43,9 @@ fn main_inner() -> Result<(), ApplicationError> {
     env_logger::Builder::new()
         .filter_level(args.verbose.log_level_filter())
         .init();
+    // Create the shared async runtime up front, so that it is not counted in the first execution.
+    // Interrupting cpt also kills the programs it runs, which are in their own process groups.
+    cpt_stdx::process::kill_children_on_signal();
     args.command.run()?;
     Ok(())
 }
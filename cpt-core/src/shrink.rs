@@ -0,0 +1,188 @@
+/// Maximum number of candidates tried in one shrinking.
+const MAX_TRIALS: usize = 1000;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to execute shrinker.")]
+    Shrinker(#[source] cpt_stdx::process::Error),
+    #[error("Cannot pass the input to shrinker.")]
+    ShrinkerInput(#[source] std::io::Error),
+}
+
+/// Source of smaller candidates of an input.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Reducer<'a> {
+    /// Built-in token-level reducers: removing lines, removing tokens and shrinking numbers.
+    Builtin,
+    /// Command reading the input from stdin and writing a candidate to stdout.
+    /// It is called once per step, and the shrinking stops when its candidate passes.
+    Command(&'a str, u64),
+}
+impl Reducer<'_> {
+    /// Candidates of the next step, generated lazily.
+    fn candidates<'s>(
+        &self,
+        input: &'s str,
+    ) -> Result<Box<dyn Iterator<Item = String> + 's>, Error> {
+        match self {
+            Reducer::Builtin => Ok(Box::new(builtin_candidates(input))),
+            Reducer::Command(command, timelimit_ms) => {
+                let candidate = run_shrinker(command, input, *timelimit_ms)?;
+                Ok(Box::new(
+                    Some(candidate)
+                        .filter(|candidate| candidate != input)
+                        .into_iter(),
+                ))
+            }
+        }
+    }
+}
+
+fn run_shrinker(command: &str, input: &str, timelimit_ms: u64) -> Result<String, Error> {
+    use std::io::{Seek, Write};
+    use std::process::Stdio;
+
+    use cpt_stdx::process::{Capture, CaptureRedirection, Command};
+
+    let mut stdin = tempfile::tempfile().map_err(Error::ShrinkerInput)?;
+    stdin
+        .write_all(input.as_bytes())
+        .and_then(|_| stdin.rewind())
+        .map_err(Error::ShrinkerInput)?;
+    let captured = Command::from(command)
+        .exec_capture(
+            CaptureRedirection {
+                stdin: Stdio::from(stdin),
+                stdout: Capture::Memory { tail: None },
+                stderr: Capture::Null,
+                limit_bytes: None,
+            },
+            timelimit_ms,
+            true,
+        )
+        .map_err(Error::Shrinker)?;
+    Ok(String::from_utf8_lossy(&captured.stdout).into_owned())
+}
+
+/// Candidates smaller than `input`, roughly in decreasing order of the reduction.
+///
+/// Each candidate is built only when it is requested.
+fn builtin_candidates(input: &str) -> impl Iterator<Item = String> + '_ {
+    use std::rc::Rc;
+
+    let lines = Rc::new(input.lines().collect::<Vec<_>>());
+    // `lines` with the `i`-th line replaced by `line`.
+    let replaced = |lines: &[&str], i: usize, line: &str| {
+        lines[..i]
+            .iter()
+            .copied()
+            .chain([line])
+            .chain(lines[i + 1..].iter().copied())
+            .flat_map(|line| [line, "\n"])
+            .collect::<String>()
+    };
+
+    // Fewer lines: removes chunks of lines, halving the chunk size.
+    let fewer_lines = {
+        let lines = lines.clone();
+        std::iter::successors(Some(lines.len() / 2), |chunk| Some(chunk / 2))
+            .take_while(|&chunk| chunk > 0)
+            .flat_map(move |chunk| {
+                let lines = lines.clone();
+                (0..lines.len()).step_by(chunk).map(move |start| {
+                    let end = (start + chunk).min(lines.len());
+                    lines[..start]
+                        .iter()
+                        .chain(&lines[end..])
+                        .flat_map(|line| [*line, "\n"])
+                        .collect::<String>()
+                })
+            })
+    };
+
+    // Fewer tokens per line.
+    let fewer_tokens = {
+        let lines = lines.clone();
+        (0..lines.len()).flat_map(move |i| {
+            let lines = lines.clone();
+            let tokens = lines[i].split_whitespace().collect::<Vec<_>>();
+            let removable = if tokens.len() < 2 { 0 } else { tokens.len() };
+            (0..removable).rev().map(move |removed| {
+                let line = tokens
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != removed)
+                    .map(|(_, token)| *token)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                replaced(&lines, i, &line)
+            })
+        })
+    };
+
+    // Smaller numbers, toward zero.
+    let smaller_numbers = (0..lines.len()).flat_map(move |i| {
+        let lines = lines.clone();
+        let tokens = lines[i].split_whitespace().collect::<Vec<_>>();
+        (0..tokens.len()).flat_map(move |j| {
+            let lines = lines.clone();
+            let tokens = tokens.clone();
+            let mut smaller = match tokens[j].parse::<i64>() {
+                Ok(number) => vec![0, number / 2, number - number.signum()]
+                    .into_iter()
+                    .filter(|&value| value != number)
+                    .collect::<Vec<_>>(),
+                Err(_) => Vec::new(),
+            };
+            smaller.dedup();
+            smaller.into_iter().map(move |value| {
+                let value = value.to_string();
+                let mut line_tokens = tokens.clone();
+                line_tokens[j] = &value;
+                replaced(&lines, i, &line_tokens.join(" "))
+            })
+        })
+    });
+
+    fewer_lines
+        .chain(fewer_tokens)
+        .chain(smaller_numbers)
+        .filter(move |candidate| candidate != input)
+}
+
+/// Greedily shrinks `input` while `still_fails` holds, and returns the minimal one found.
+///
+/// The first failing candidate in each step is adopted, until no candidate fails
+/// or `MAX_TRIALS` candidates are tried.
+pub(crate) fn shrink<E>(
+    input: String,
+    reducer: Reducer,
+    mut still_fails: impl FnMut(&str) -> Result<bool, E>,
+) -> Result<String, E>
+where
+    E: From<Error>,
+{
+    log::info!("[Shrink] Start ({} bytes)", input.len());
+    let mut current = input;
+    let mut trials = 0;
+    while trials < MAX_TRIALS {
+        let mut adopted = None;
+        for candidate in reducer.candidates(&current)? {
+            if trials >= MAX_TRIALS {
+                break;
+            }
+            trials += 1;
+            if still_fails(&candidate)? {
+                log::info!("[Shrink][Trial {}] {} bytes", trials, candidate.len());
+                adopted = Some(candidate);
+                break;
+            }
+        }
+        match adopted {
+            Some(candidate) => current = candidate,
+            None => break,
+        }
+    }
+    log::info!("[Shrink] End ({} bytes, {} trials)", current.len(), trials);
+    Ok(current)
+}
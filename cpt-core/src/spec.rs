@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Cannot read `{0}`.")]
+    Read(PathBuf, #[source] cpt_stdx::fs::Error),
+    #[error("Cannot parse `{0}`.")]
+    Parse(PathBuf, #[source] Box<toml::de::Error>),
+    #[error("`{0}` is neither defined in the spec nor given by `--param`.")]
+    UndefinedVariable(String),
+    #[error("`{0}` = `{1}` is not an integer.")]
+    NotInteger(String, String),
+    #[error("Empty range [{0}, {1}].")]
+    EmptyRange(i64, i64),
+    #[error("No {0} with {1} vertices and {2} edges.")]
+    Infeasible(&'static str, i64, i64),
+    #[error("`distinct` cannot be combined with `distribution` in `{0}`.")]
+    DistinctWithDistribution(PathBuf),
+    #[error("`alphabet` of a string is empty in `{0}`.")]
+    EmptyAlphabet(PathBuf),
+    #[error("Negative length `len` = {0}.")]
+    NegativeLength(i64),
+}
+
+/// Input format for the built-in generator, read from `--spec`.
+///
+/// Items are written in order. Bounds are either integers or names of
+/// earlier `int` items or of `--param` parameters.
+///
+/// ```toml
+/// [[item]]
+/// type = "int"
+/// name = "n"
+/// min = 2
+/// max = "N"   # e.g. `--param N=10,1000,100000`
+/// end = " "   # separator written after the value (default: newline)
+///
+/// [[item]]
+/// type = "int"
+/// name = "m"
+/// min = "n"
+/// max = 100
+///
+/// [[item]]
+/// type = "graph" # "m" edges of a simple graph on "n" vertices
+/// n = "n"
+/// m = "m"
+/// connected = true
+/// ```
+#[derive(serde::Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Spec {
+    item: Vec<Item>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum Bound {
+    Value(i64),
+    Variable(String),
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+enum Item {
+    /// An integer in `[min, max]`, which can be referred to by `name`.
+    Int {
+        name: Option<String>,
+        min: Bound,
+        max: Bound,
+        #[serde(default = "default_end")]
+        end: String,
+    },
+    /// `len` integers in `[min, max]` on a line.
+    Array {
+        len: Bound,
+        min: Bound,
+        max: Bound,
+        #[serde(default)]
+        distinct: bool,
+        distribution: Option<Distribution>,
+    },
+    /// A string of length `len` over `alphabet`.
+    String {
+        len: Bound,
+        #[serde(default = "default_alphabet")]
+        alphabet: String,
+    },
+    /// A permutation of `1..=len` on a line.
+    Permutation { len: Bound },
+    /// `n - 1` edges `u v` of a random tree of `shape` on vertices `1..=n`.
+    Tree {
+        n: Bound,
+        #[serde(default)]
+        shape: TreeShape,
+    },
+    /// `m` edges `u v` of a simple graph on vertices `1..=n`.
+    Graph {
+        n: Bound,
+        m: Bound,
+        #[serde(default)]
+        connected: bool,
+    },
+}
+
+/// [`cpt_stdx::random::Distribution`] of an array.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+enum Distribution {
+    #[default]
+    Uniform,
+    AllEqual,
+    Sorted,
+    AntiHash,
+    MaxValue,
+}
+impl From<Distribution> for cpt_stdx::random::Distribution {
+    fn from(distribution: Distribution) -> Self {
+        match distribution {
+            Distribution::Uniform => Self::Uniform,
+            Distribution::AllEqual => Self::AllEqual,
+            Distribution::Sorted => Self::Sorted,
+            Distribution::AntiHash => Self::AntiHash,
+            Distribution::MaxValue => Self::MaxValue,
+        }
+    }
+}
+
+/// [`cpt_stdx::random::TreeShape`] of a tree.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+enum TreeShape {
+    #[default]
+    Recursive,
+    Uniform,
+    Path,
+    Star,
+}
+impl From<TreeShape> for cpt_stdx::random::TreeShape {
+    fn from(shape: TreeShape) -> Self {
+        match shape {
+            TreeShape::Recursive => Self::Recursive,
+            TreeShape::Uniform => Self::Uniform,
+            TreeShape::Path => Self::Path,
+            TreeShape::Star => Self::Star,
+        }
+    }
+}
+
+fn default_end() -> String {
+    "\n".to_owned()
+}
+
+fn default_alphabet() -> String {
+    ('a'..='z').collect()
+}
+
+/// Loads the spec from `path`.
+pub(crate) fn load(path: &Path) -> Result<Spec, Error> {
+    let content = cpt_stdx::fs::read(path).map_err(|e| Error::Read(path.to_owned(), e))?;
+    let spec: Spec =
+        toml::from_str(&content).map_err(|e| Error::Parse(path.to_owned(), Box::new(e)))?;
+    for item in &spec.item {
+        match item {
+            // Distinct values are always uniform, so a distribution would be silently ignored.
+            Item::Array {
+                distinct: true,
+                distribution: Some(_),
+                ..
+            } => return Err(Error::DistinctWithDistribution(path.to_owned())),
+            Item::String { alphabet, .. } if alphabet.is_empty() => {
+                return Err(Error::EmptyAlphabet(path.to_owned()))
+            }
+            _ => {}
+        }
+        // A length given by a variable is checked on generation.
+        if let Item::Array { len, .. } | Item::String { len, .. } | Item::Permutation { len } = item
+        {
+            if let Bound::Value(len @ ..0) = len {
+                return Err(Error::NegativeLength(*len));
+            }
+        }
+    }
+    Ok(spec)
+}
+
+impl Spec {
+    /// Generates an input from `seed`, with the parameters `params` of the trial.
+    pub(crate) fn generate(
+        &self,
+        seed: u64,
+        params: &BTreeMap<String, String>,
+    ) -> Result<String, Error> {
+        use std::fmt::Write;
+
+        use itertools::Itertools;
+
+        let mut rng = cpt_stdx::random::Rng::new(seed);
+        let mut variables = BTreeMap::new();
+        let resolve = |variables: &BTreeMap<String, i64>, bound: &Bound| match bound {
+            Bound::Value(value) => Ok(*value),
+            Bound::Variable(name) => match variables.get(name) {
+                Some(value) => Ok(*value),
+                None => {
+                    let value = params
+                        .get(name)
+                        .ok_or_else(|| Error::UndefinedVariable(name.to_owned()))?;
+                    value
+                        .parse()
+                        .map_err(|_| Error::NotInteger(name.to_owned(), value.to_owned()))
+                }
+            },
+        };
+        let range = |variables: &BTreeMap<String, i64>, min: &Bound, max: &Bound| {
+            let (min, max) = (resolve(variables, min)?, resolve(variables, max)?);
+            if min > max {
+                return Err(Error::EmptyRange(min, max));
+            }
+            Ok((min, max))
+        };
+        let size = |variables: &BTreeMap<String, i64>, len: &Bound| match resolve(variables, len)? {
+            len @ ..0 => Err(Error::NegativeLength(len)),
+            len => Ok(len as usize),
+        };
+        let edges = |edges: Vec<(usize, usize)>| {
+            edges.into_iter().fold(String::new(), |mut lines, (u, v)| {
+                let _ = writeln!(lines, "{} {}", u, v);
+                lines
+            })
+        };
+
+        let mut input = String::new();
+        for item in &self.item {
+            match item {
+                Item::Int {
+                    name,
+                    min,
+                    max,
+                    end,
+                } => {
+                    let (min, max) = range(&variables, min, max)?;
+                    let value = rng.range(min, max);
+                    if let Some(name) = name {
+                        variables.insert(name.to_owned(), value);
+                    }
+                    input += &format!("{}{}", value, end);
+                }
+                Item::Array {
+                    len,
+                    min,
+                    max,
+                    distinct,
+                    distribution,
+                } => {
+                    let len = size(&variables, len)?;
+                    let (min, max) = range(&variables, min, max)?;
+                    let values = if *distinct {
+                        rng.distinct(len, min, max)
+                            .ok_or(Error::EmptyRange(min, max))?
+                    } else {
+                        rng.array(len, min, max, distribution.unwrap_or_default().into())
+                    };
+                    input += &format!("{}\n", values.iter().join(" "));
+                }
+                Item::String { len, alphabet } => {
+                    let len = size(&variables, len)?;
+                    let alphabet = alphabet.chars().collect::<Vec<_>>();
+                    // The alphabet is checked to be non-empty on load.
+                    let string = rng.string(len, &alphabet).unwrap();
+                    input += &format!("{}\n", string);
+                }
+                Item::Permutation { len } => {
+                    let len = size(&variables, len)?;
+                    input += &format!("{}\n", rng.permutation(len).iter().join(" "));
+                }
+                Item::Tree { n, shape } => {
+                    let n = resolve(&variables, n)?;
+                    if n < 1 {
+                        return Err(Error::Infeasible("tree", n, n - 1));
+                    }
+                    input += &edges(rng.tree(n as usize, (*shape).into()));
+                }
+                Item::Graph { n, m, connected } => {
+                    let (n, m) = (resolve(&variables, n)?, resolve(&variables, m)?);
+                    if n < 1 || m < 0 {
+                        return Err(Error::Infeasible("graph", n, m));
+                    }
+                    let graph = rng
+                        .graph(n as usize, m as usize, *connected)
+                        .ok_or(Error::Infeasible("graph", n, m))?;
+                    input += &edges(graph);
+                }
+            }
+        }
+        Ok(input)
+    }
+}
@@ -8,6 +8,8 @@ const HACKCASE_PREFIX: &str = "Generated_";
 pub(crate) enum Error {
     #[error("Failed to copy testcase files.")]
     Copy(#[from] cpt_stdx::fs::Error),
+    #[error("Failed to write testcase files.")]
+    Write(#[source] cpt_stdx::fs::Error),
 }
 
 #[derive(Debug, Clone)]
@@ -39,41 +41,98 @@ pub(crate) fn collect(dir: &Path) -> Vec<Testcase> {
     cases
 }
 
-pub(crate) fn new_hackcase(dir: &Path) -> (Testcase, Testcase) {
-    use std::collections::BTreeSet;
-    let mut hackcases = BTreeSet::<String>::new();
-    for cases in collect(dir) {
-        if let Some(hackcase) = cases.casename.strip_prefix(HACKCASE_PREFIX) {
-            hackcases.insert(hackcase.to_owned());
-        }
+/// Working case of a hack session in its private `temp_dir`.
+pub(crate) fn new_hackcase(temp_dir: &Path) -> Testcase {
+    let casename = HACKCASE_PREFIX.trim_end_matches('_').to_owned();
+    Testcase {
+        input: temp_dir.join(casename.to_owned() + "." + INPUT_EXT),
+        output: Some(temp_dir.join(casename.to_owned() + "." + OUTPUT_EXT)),
+        casename,
     }
+}
+
+/// Allocates a new `Generated_<n>` case in `dir`.
+///
+/// The input file is created exclusively, so that concurrent sessions never get the same name.
+fn allocate_hackcase(dir: &Path) -> Result<Testcase, Error> {
+    use std::io::ErrorKind;
+
     let mut no = 0;
     loop {
-        if !hackcases.contains(&no.to_string()) {
-            let casename = format!("{}{}", HACKCASE_PREFIX, no);
-            let temp_dir = std::env::temp_dir();
-            let temp_case = Testcase {
-                casename: casename.clone(),
-                input: temp_dir.join(casename.clone() + "." + INPUT_EXT),
-                output: Some(temp_dir.join(casename.clone() + "." + OUTPUT_EXT)),
-            };
-            let final_case = Testcase {
-                casename,
-                input: dir
-                    .to_owned()
-                    .join(temp_case.casename.clone() + "." + INPUT_EXT),
-                output: Some(
-                    dir.to_owned()
-                        .join(temp_case.casename.clone() + "." + OUTPUT_EXT),
-                ),
-            };
-            return (temp_case, final_case);
+        let casename = format!("{}{}", HACKCASE_PREFIX, no);
+        let input = dir.join(casename.to_owned() + "." + INPUT_EXT);
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&input)
+        {
+            Ok(_) => {
+                return Ok(Testcase {
+                    output: Some(dir.join(casename.to_owned() + "." + OUTPUT_EXT)),
+                    casename,
+                    input,
+                })
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => no += 1,
+            Err(_) => return Err(Error::Write(cpt_stdx::fs::Error::CreateFile(input))),
         }
-        no += 1;
+    }
+}
+
+/// Destination of a hack session in the testcase directory.
+///
+/// The case is allocated on the first save, and overwritten by the later ones.
+pub(crate) struct Hackcase {
+    dir: PathBuf,
+    case: Option<Testcase>,
+}
+impl Hackcase {
+    pub(crate) fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_owned(),
+            case: None,
+        }
+    }
+
+    /// Saves the generated `temp_case` with its intermediate files in `temp_dir`,
+    /// and the generator parameters as `<casename>.param.txt` unless `params` is empty.
+    pub(crate) fn save(
+        &mut self,
+        temp_case: &Testcase,
+        temp_dir: &Path,
+        params: &str,
+    ) -> Result<&Testcase, Error> {
+        let (case, allocated) = match self.case.take() {
+            Some(case) => (case, false),
+            None => (allocate_hackcase(&self.dir)?, true),
+        };
+        if let Err(e) = temp_case.copy_to_with_intermediate_files(&case, temp_dir, &self.dir) {
+            // A newly allocated case is released, so that no empty input is left.
+            if allocated {
+                let _ = std::fs::remove_file(&case.input);
+                if let Some(output) = &case.output {
+                    let _ = std::fs::remove_file(output);
+                }
+            } else {
+                self.case = Some(case);
+            }
+            return Err(e);
+        }
+        let case = self.case.insert(case);
+        if !params.is_empty() {
+            case.write_params(&self.dir, params)?;
+        }
+        log::info!("[Hack] Saved `{}`", case.input.display());
+        Ok(case)
     }
 }
 
 impl Testcase {
+    /// Path of the expected output next to the input, whether it exists or not.
+    pub(crate) fn output_path(&self) -> PathBuf {
+        self.input.with_extension(OUTPUT_EXT)
+    }
+
     pub(crate) fn copy_to(&self, target: &Testcase) -> Result<(), Error> {
         cpt_stdx::fs::copy(&self.input, &target.input)?;
         if let (Some(src_output), Some(target_output)) = (&self.output, &target.output) {
@@ -82,6 +141,16 @@ impl Testcase {
         Ok(())
     }
 
+    /// Records the generator parameters of this case as `<casename>.param.txt` in `dir`.
+    fn write_params(&self, dir: &Path, params: &str) -> Result<(), Error> {
+        cpt_stdx::fs::write(
+            dir.join(self.casename.to_owned() + ".param.txt"),
+            params.to_owned() + "\n",
+            true,
+        )
+        .map_err(Error::Write)
+    }
+
     pub(crate) fn copy_to_with_intermediate_files(
         &self,
         target: &Testcase,
@@ -95,7 +164,7 @@ impl Testcase {
         let intermediate_extensions = ["actual.txt", "debug.txt", "judge.txt"];
         for ext in &intermediate_extensions {
             let temp_file = temp_dir.join(format!("{}.{}", self.casename, ext));
-            let final_file = final_dir.join(format!("{}.{}", self.casename, ext));
+            let final_file = final_dir.join(format!("{}.{}", target.casename, ext));
 
             if temp_file.exists() && cpt_stdx::fs::copy(&temp_file, &final_file).is_err() {
                 // Intermediate files are optional, so we don't fail if copy fails
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to execute validator.")]
+    Validator(#[source] cpt_stdx::process::Error),
+    #[error("Cannot open `{0}`.")]
+    Input(std::path::PathBuf, #[source] cpt_stdx::fs::Error),
+}
+
+/// Runs the validator on `input` as in testlib: the input is given on stdin,
+/// and a non-zero exit means the input is invalid.
+///
+/// Returns the reason (the validator's stderr) if invalid.
+pub(crate) fn validate(
+    validator_command: &str,
+    input: &Path,
+    timelimit_ms: u64,
+) -> Result<Option<String>, Error> {
+    use std::process::Stdio;
+
+    use cpt_stdx::fs;
+    use cpt_stdx::process::{Command, IoRedirection, Status, StatusSummary};
+
+    let stdin = fs::open(input).map_err(|e| Error::Input(input.to_owned(), e))?;
+    let Status { summary, detail } = Command::from(validator_command)
+        .exec(
+            IoRedirection {
+                stdin: Stdio::from(stdin),
+                stdout: Stdio::null(),
+                stderr: Stdio::piped(),
+            },
+            timelimit_ms,
+            false,
+        )
+        .map_err(Error::Validator)?;
+    Ok(match summary {
+        StatusSummary::Success => None,
+        StatusSummary::Timeout => Some(format!("validator timed out ({}ms)", timelimit_ms)),
+        StatusSummary::Aborted | StatusSummary::OutputLimitExceeded => {
+            let reason = detail.stderr.trim();
+            Some(if reason.is_empty() {
+                detail
+                    .termination()
+                    .map(|termination| termination.to_string())
+                    .unwrap_or_default()
+            } else {
+                reason.to_owned()
+            })
+        }
+    })
+}
+
+/// Validates the inputs of `cases`, logging the invalid ones.
+///
+/// Returns the number of invalid inputs.
+pub(crate) fn validate_cases(
+    validator_command: &str,
+    cases: &[crate::testcase::Testcase],
+    timelimit_ms: u64,
+) -> Result<usize, Error> {
+    let mut invalid = 0;
+    for case in cases {
+        if let Some(reason) = validate(validator_command, &case.input, timelimit_ms)? {
+            log::warn!("[Validate][{}] Invalid: {}", case.casename, reason);
+            invalid += 1;
+        }
+    }
+    Ok(invalid)
+}
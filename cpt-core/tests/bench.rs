@@ -0,0 +1,162 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+#[test]
+fn bench_reports_statistics() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "0\n", true);
+        write_sync(tempdir.path().join("2.in"), "0.3\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        // Keeps the calibration of this machine (`cpt calibrate`) out of the time limit.
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"));
+        cmd.args([
+            "bench",
+            "-c",
+            "xargs sleep",
+            "--repeat",
+            "3",
+            "-t",
+            "1000",
+            "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Bench][1] Wall: "))
+            .stderr(predicate::str::contains("(min/median/max)"))
+            .stderr(predicate::str::contains("[Bench] Slowest: 2 ("))
+            .stderr(predicate::str::contains(
+                "[Bench] End (Near TLE:0,Failed:0)",
+            ));
+    });
+}
+
+#[test]
+fn bench_warns_near_tle() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "0.3\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        // Keeps the calibration of this machine (`cpt calibrate`) out of the time limit.
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"));
+        cmd.args([
+            "bench",
+            "-c",
+            "xargs sleep",
+            "--repeat",
+            "1",
+            "-t",
+            "1000",
+            "--near-tle",
+            "20",
+            "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Bench][1] Near TLE"))
+            .stderr(predicate::str::contains(
+                "[Bench] End (Near TLE:1,Failed:0)",
+            ));
+    });
+}
+
+#[test]
+fn bench_repeat_must_be_positive() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["bench", "-c", "cat", "--repeat", "0", "-d"])
+            .arg(tempdir.path());
+        cmd.assert().failure();
+    });
+}
+
+#[test]
+fn bench_against_baseline() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "0.3\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "bench",
+            "--baseline",
+            "cat",
+            "-c",
+            "xargs sleep",
+            "--repeat",
+            "3",
+            "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Bench][1] Baseline Wall: "))
+            .stderr(predicate::str::contains("(high confidence)"))
+            .stderr(predicate::str::contains("[Bench][1] Regression: "))
+            .stderr(predicate::str::contains(
+                "[Bench] Baseline (Faster:0,Slower:1,Unclear:0)",
+            ));
+        assert!(!tempdir.path().join("1.baseline.actual.txt").exists());
+        assert!(!tempdir.path().join("1.actual.txt").exists());
+    });
+}
+
+#[test]
+fn bench_excludes_non_ac_runs() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+        write_sync(tempdir.path().join("1.out"), "2\n", true);
+        write_sync(tempdir.path().join("2.in"), "2\n", true);
+        write_sync(tempdir.path().join("2.out"), "2\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["bench", "-c", "cat", "--repeat", "3", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Bench][1] Excluded non-AC run(s) (WA:3)",
+            ))
+            .stderr(predicate::str::contains("[Bench][1] Wall: ").not())
+            .stderr(predicate::str::contains("[Bench][2] Wall: "))
+            .stderr(predicate::str::contains(
+                "[Bench] End (Near TLE:0,Failed:1)",
+            ));
+    });
+}
+
+#[test]
+fn bench_confidence_needs_paired_runs() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "0.3\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "bench",
+            "--baseline",
+            "cat",
+            "-c",
+            "xargs sleep",
+            "--repeat",
+            "2",
+            "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "(confidence n/a, 2 paired run(s))",
+            ))
+            .stderr(predicate::str::contains("Regression").not())
+            .stderr(predicate::str::contains(
+                "[Bench] Baseline (Faster:0,Slower:0,Unclear:1)",
+            ));
+    });
+}
@@ -0,0 +1,103 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+#[test]
+fn compare_groups_outputs() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+        write_sync(tempdir.path().join("2.in"), "2\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["compare", "-c", "cat", "-c", "cat", "-c", "echo 1", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Compare][1] Agree"))
+            .stderr(predicate::str::contains(
+                "[Compare][2] Disagree: {#1,#2} / {#3}",
+            ))
+            .stderr(predicate::str::contains(
+                "[Compare] End (Agree:1,Disagree:1)",
+            ));
+        let read = |name: &str| std::fs::read_to_string(tempdir.path().join(name)).unwrap();
+        assert_eq!(read("2.1.actual.txt"), "2\n");
+        assert_eq!(read("2.3.actual.txt"), "1\n");
+    });
+}
+
+#[test]
+fn compare_groups_failures_by_verdict() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["compare", "-c", "cat", "-c", "false", "-c", "false", "-d"])
+            .arg(tempdir.path());
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Compare][1] Disagree: {#1} / {#2,#3}",
+        ));
+    });
+}
+
+#[test]
+fn compare_too_few_solutions() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["compare", "-c", "cat", "-d"]).arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("At least 2 solutions"));
+    });
+}
+
+#[test]
+fn hack_compare_finds_disagreement() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack", "compare", "-c", "cat", "-c", "echo 1", "-i", "echo", "--param", "1..3", "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Compare Hack][Trial 1] End: Agree",
+            ))
+            .stderr(predicate::str::contains(
+                "[Compare Hack][Trial 2] End: Disagree",
+            ));
+        let read = |name: &str| std::fs::read_to_string(tempdir.path().join(name)).unwrap();
+        assert_eq!(read("Generated_0.in"), "2\n");
+        assert_eq!(read("Generated_0.1.actual.txt"), "2\n");
+        assert_eq!(read("Generated_0.2.actual.txt"), "1\n");
+        assert!(!tempdir.path().join("Generated_0.out").exists());
+    });
+}
+
+#[test]
+fn hack_compare_stops_after_trials() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack", "compare", "-c", "cat", "-c", "cat", "-i", "echo", "--trials", "3", "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Compare Hack][Trial 3] End: Agree",
+            ))
+            .stderr(predicate::str::contains(
+                "[Compare Hack] Not found in 3 trial(s)",
+            ));
+        assert!(!tempdir.path().join("Generated_0.in").exists());
+    });
+}
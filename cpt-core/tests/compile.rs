@@ -0,0 +1,124 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+const SHELL_PROFILE: &str =
+    "[languages.shell]\nextensions = [\"sh\"]\ncompile = \"install -m 755 {source} {binary}\"\nrun = \"{binary}\"\n";
+
+#[test]
+fn compile_and_test() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("cpt.toml"), SHELL_PROFILE, true);
+        write_sync(tempdir.path().join("main.sh"), "#!/bin/sh\ncat\n", true);
+        write_sync(tempdir.path().join("cases/AC_1.in"), "abc", true);
+        write_sync(tempdir.path().join("cases/AC_1.out"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "-s", "main.sh", "batch", "-d", "cases"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Compile][main.sh] End"))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+        assert!(tempdir.path().join("main.exe").is_file());
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "batch", "-s", "main.sh", "-d", "cases"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Compile][main.sh] Up to date"))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+    });
+}
+
+#[test]
+fn compile_error_before_cases() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "[languages.shell]\nextensions = [\"sh\"]\ncompile = \"sh -n {source}\"\nrun = \"sh {source}\"\n",
+            true,
+        );
+        write_sync(tempdir.path().join("main.sh"), "if then\n", true);
+        write_sync(tempdir.path().join("cases/AC_1.in"), "abc", true);
+        write_sync(tempdir.path().join("cases/AC_1.out"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "batch", "-s", "main.sh", "-d", "cases"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Compile][main.sh] CE"))
+            .stderr(predicate::str::contains("[Judge]").not())
+            .stderr(predicate::str::contains("[Batch Test] End (AC:0,"))
+            .stderr(predicate::str::contains(",CE:1)"));
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["hack", "batch", "-s", "main.sh", "-i", "echo abc"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Compile error."));
+    });
+}
+
+#[test]
+fn compile_timeout() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "[languages.shell]\nextensions = [\"sh\"]\ncompile = \"sleep 1\"\nrun = \"sh {source}\"\ncompile_timeout_ms = 100\n",
+            true,
+        );
+        write_sync(tempdir.path().join("main.sh"), "cat\n", true);
+        write_sync(tempdir.path().join("cases/AC_1.in"), "abc", true);
+        write_sync(tempdir.path().join("cases/AC_1.out"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "batch", "-s", "main.sh", "-d", "cases"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Compile][main.sh] CE (Timeout: "))
+            .stderr(predicate::str::contains(",CE:1)"));
+    });
+}
+
+#[test]
+fn command_option_skips_compile() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("cpt.toml"), SHELL_PROFILE, true);
+        write_sync(tempdir.path().join("main.sh"), "#!/bin/sh\ncat\n", true);
+        write_sync(tempdir.path().join("cases/AC_1.in"), "abc", true);
+        write_sync(tempdir.path().join("cases/AC_1.out"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "batch", "-s", "main.sh", "-c", "cat", "-d", "cases"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Compile]").not())
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+        assert!(!tempdir.path().join("main.exe").exists());
+    });
+}
+
+#[test]
+fn compile_unknown_language() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("main.xyz"), "", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "batch", "-s", "main.xyz", "-d", "cases"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("No language profile"));
+    });
+}
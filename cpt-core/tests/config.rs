@@ -0,0 +1,112 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+#[test]
+fn config_selects_batch_test() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "command = \"cat\"\ndirectory = \"cases\"\n",
+            true,
+        );
+        write_sync(tempdir.path().join("cases/AC_1.in"), "abc", true);
+        write_sync(tempdir.path().join("cases/AC_1.out"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path()).args(["test"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+    });
+}
+
+#[test]
+fn config_per_problem_table() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "directory = \"cases\"\n\n[problems.a]\ncommand = \"cat\"\njudge = \"special\"\njudge_command = \"true\"\n",
+            true,
+        );
+        write_sync(tempdir.path().join("a/cases/AC_1.in"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path().join("a")).args(["t"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Special Test] End (AC:1,"));
+    });
+}
+
+#[test]
+fn config_overridden_by_option() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "command = \"cat\"\ndirectory = \"cases\"\n",
+            true,
+        );
+        write_sync(tempdir.path().join("cases/RE_1.in"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "batch", "-c", "false"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("RE (exit 1)"));
+    });
+}
+
+#[test]
+fn config_parse_error() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "timelimit_ms = \"a\"",
+            true,
+        );
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path()).args(["test"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Cannot parse"));
+    });
+}
+
+#[test]
+fn config_unknown_field() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "[problems.a]\ntimelimit = 1000\n",
+            true,
+        );
+        std::fs::create_dir(tempdir.path().join("a")).unwrap();
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path().join("a")).args(["test"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Cannot parse"))
+            .stderr(predicate::str::contains("unknown field `timelimit`"));
+    });
+}
+
+#[test]
+fn option_not_specified() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .args(["test", "batch", "-d", "."]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("`-c` is specified neither"));
+    });
+}
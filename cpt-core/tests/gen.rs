@@ -0,0 +1,159 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+#[test]
+fn gen_batches_with_seeds() {
+    with_tempdir(|tempdir| {
+        let dir = tempdir.path().join("tests");
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "gen", "-i", "echo", "-o", "cat", "-n", "2", "--batch", "a", "--batch", "b", "-d",
+        ])
+        .arg(&dir);
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Gen] End (Generated:4,Invalid:0)",
+        ));
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("01.in"), "a 1\n");
+        assert_eq!(read("03.in"), "b 3\n");
+        assert_eq!(read("04.out"), "b 4\n");
+        assert_eq!(
+            read("manifest.tsv"),
+            "01\t1\techo a 1\tunchecked\n\
+             02\t2\techo a 2\tunchecked\n\
+             03\t3\techo b 3\tunchecked\n\
+             04\t4\techo b 4\tunchecked\n"
+        );
+    });
+}
+
+#[test]
+fn gen_refuses_to_overwrite() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("01.in"), "old\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen", "-i", "echo", "-d"]).arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("already exists"));
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen", "-i", "echo", "--seed", "7", "--force", "-d"])
+            .arg(tempdir.path());
+        cmd.assert().success();
+        let input = std::fs::read_to_string(tempdir.path().join("01.in")).unwrap();
+        assert_eq!(input, "7\n");
+    });
+}
+
+#[test]
+fn gen_validates_each_case() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "gen",
+            "-i",
+            "echo",
+            "-n",
+            "2",
+            "--batch",
+            "x",
+            "--validator",
+            "grep -qE ^[0-9]+$",
+            "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("[Gen][01] Invalid input"))
+            .stderr(predicate::str::contains("2 invalid input(s) generated"));
+        let manifest = std::fs::read_to_string(tempdir.path().join("manifest.tsv")).unwrap();
+        assert!(manifest.contains("01\t1\techo x 1\tinvalid"));
+    });
+}
+
+#[test]
+fn gen_force_removes_stale_outputs() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("01.in"), "old\n", true);
+        write_sync(tempdir.path().join("01.out"), "old\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen", "-i", "echo", "--force", "-d"])
+            .arg(tempdir.path());
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Gen][01] Removed the stale output",
+        ));
+        assert!(!tempdir.path().join("01.out").exists());
+    });
+}
+
+#[test]
+fn gen_records_cases_before_failure() {
+    with_tempdir(|tempdir| {
+        let dir = tempdir.path().join("tests");
+        // Fails on the second seed.
+        let generator = tempdir.path().join("gen.sh");
+        write_sync(&generator, "[ \"$1\" = 2 ] && exit 1\necho $1\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "gen",
+            "-i",
+            &format!("sh {}", generator.display()),
+            "-n",
+            "2",
+            "-d",
+        ])
+        .arg(&dir);
+        cmd.assert().failure();
+        let manifest = std::fs::read_to_string(dir.join("manifest.tsv")).unwrap();
+        assert_eq!(
+            manifest,
+            format!("01\t1\tsh {} 1\tunchecked\n", generator.display())
+        );
+        // No partial input of the failed case is left.
+        assert!(!dir.join("02.in").exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    });
+}
+
+#[test]
+fn gen_force_failure_keeps_case() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("01.in"), "good\n", true);
+        write_sync(tempdir.path().join("01.out"), "good\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen", "-i", "false", "-o", "cat", "--force", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Generation failed"));
+        let read = |name: &str| std::fs::read_to_string(tempdir.path().join(name)).unwrap();
+        assert_eq!(read("01.in"), "good\n");
+        assert_eq!(read("01.out"), "good\n");
+    });
+}
+
+#[test]
+fn gen_reports_uncreatable_directory() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("file"), "", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen", "-i", "echo", "-d"])
+            .arg(tempdir.path().join("file").join("tests"));
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Cannot create"));
+    });
+}
@@ -0,0 +1,81 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+#[test]
+fn gen_out_fills_missing_outputs() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+        write_sync(tempdir.path().join("2.in"), "2\n", true);
+        write_sync(tempdir.path().join("2.out"), "old\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen-out", "-o", "cat", "-d"]).arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Gen Out][1] Created"))
+            .stderr(predicate::str::contains("[Gen Out][2] Skipped"))
+            .stderr(predicate::str::contains(
+                "[Gen Out] End (Created:1,Overwritten:0,Skipped:1)",
+            ));
+        let read = |name: &str| std::fs::read_to_string(tempdir.path().join(name)).unwrap();
+        assert_eq!(read("1.out"), "1\n");
+        assert_eq!(read("2.out"), "old\n");
+    });
+}
+
+#[test]
+fn gen_out_force_overwrites() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+        write_sync(tempdir.path().join("1.out"), "old\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen-out", "-o", "cat", "--force", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Gen Out][1] Overwritten"));
+        let output = std::fs::read_to_string(tempdir.path().join("1.out")).unwrap();
+        assert_eq!(output, "1\n");
+    });
+}
+
+#[test]
+fn gen_out_reference_failure() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen-out", "-o", "false", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Generation failed"));
+        // Neither the output nor its temporary file is left.
+        assert!(!tempdir.path().join("1.out").exists());
+        assert_eq!(std::fs::read_dir(tempdir.path()).unwrap().count(), 1);
+    });
+}
+
+#[test]
+fn gen_out_force_failure_keeps_output() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "1\n", true);
+        write_sync(tempdir.path().join("1.out"), "good\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["gen-out", "-o", "false", "--force", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Generation failed"));
+        let output = std::fs::read_to_string(tempdir.path().join("1.out")).unwrap();
+        assert_eq!(output, "good\n");
+    });
+}
@@ -5,7 +5,7 @@ use std::process::Command;
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
 
-use crate::common::{with_tempdir, CRATE_NAME};
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
 
 #[test]
 fn hack_batch_directory_not_found() {
@@ -118,3 +118,523 @@ fn hack_batch_short_alias() {
             .stderr(predicate::str::contains("Failed to spawn"));
     });
 }
+
+#[test]
+fn hack_batch_output_limit_exceeded() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "yes",
+            "-i",
+            "echo hello",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--output-limit",
+            "1",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("OLE"));
+        assert!(tempdir.path().join("Generated_0.in").exists());
+    });
+}
+
+#[test]
+fn hack_batch_shrink_builtin() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "tac",
+            "-i",
+            "seq 1 20",
+            "-o",
+            "cat",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--shrink",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Shrink] End"));
+        let input = std::fs::read_to_string(tempdir.path().join("Generated_0.in")).unwrap();
+        assert_eq!(input.lines().count(), 2);
+        let output = std::fs::read_to_string(tempdir.path().join("Generated_0.out")).unwrap();
+        assert_eq!(input, output);
+    });
+}
+
+#[test]
+fn hack_batch_shrink_with_shrinker() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "tac",
+            "-i",
+            "seq 1 20",
+            "-o",
+            "cat",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--shrinker",
+            "head -n 3",
+        ]);
+        cmd.assert().success();
+        let input = std::fs::read_to_string(tempdir.path().join("Generated_0.in")).unwrap();
+        assert_eq!(input, "1\n2\n3\n");
+    });
+}
+
+#[test]
+fn hack_batch_param_schedule() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "cat",
+            "-i",
+            "echo",
+            "-o",
+            "echo 1",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--param",
+            "1..3,10",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Batch Hack][Trial 2] Params: 2"));
+        let params = std::fs::read_to_string(tempdir.path().join("Generated_0.param.txt")).unwrap();
+        assert_eq!(params, "2\n");
+    });
+}
+
+#[test]
+fn hack_batch_param_huge_range() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "false",
+            "-i",
+            "echo",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--param",
+            "n=-9223372036854775808..9223372036854775807",
+        ]);
+        cmd.assert().success();
+        let params = std::fs::read_to_string(tempdir.path().join("Generated_0.param.txt")).unwrap();
+        assert_eq!(params, "n=-9223372036854775808\n");
+    });
+}
+
+#[test]
+fn hack_batch_named_param() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "cat",
+            "-i",
+            "echo",
+            "-o",
+            "echo 1",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--param",
+            "n=5",
+        ]);
+        cmd.assert().success();
+        let input = std::fs::read_to_string(tempdir.path().join("Generated_0.in")).unwrap();
+        assert_eq!(input, "5");
+        let params = std::fs::read_to_string(tempdir.path().join("Generated_0.param.txt")).unwrap();
+        assert_eq!(params, "n=5\n");
+    });
+}
+
+#[test]
+fn hack_batch_invalid_param() {
+    let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+    cmd.args([
+        "hack", "batch", "-c", "cat", "-i", "echo", "-d", ".", "--param", "n=1..x",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("is not an integer"));
+}
+
+#[test]
+fn hack_batch_target_verdict() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "xargs test 3 -gt",
+            "-i",
+            "echo",
+            "-o",
+            "echo x",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--param",
+            "1..5",
+            "--target",
+            "re",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Batch Hack][Trial 1] End: WA"))
+            .stderr(predicate::str::contains("[Batch Hack][Trial 3] End: RE"));
+        let params = std::fs::read_to_string(tempdir.path().join("Generated_0.param.txt")).unwrap();
+        assert_eq!(params, "3\n");
+    });
+}
+
+#[test]
+fn hack_batch_slowest() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "xargs sleep",
+            "-i",
+            "echo",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--param",
+            "0.1,0.4,0.2",
+            "--slowest",
+            "3",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("(Trial 2)"));
+        let input = std::fs::read_to_string(tempdir.path().join("Generated_0.in")).unwrap();
+        assert_eq!(input, "0.4\n");
+    });
+}
+
+#[test]
+fn hack_batch_slowest_conflicts_with_target() {
+    let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+    cmd.args([
+        "hack",
+        "batch",
+        "-c",
+        "cat",
+        "-i",
+        "echo",
+        "-d",
+        ".",
+        "--slowest",
+        "3",
+        "--target",
+        "tle",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn hack_batch_corpus_and_replay() {
+    with_tempdir(|tempdir| {
+        let corpus = tempdir.path().join("corpus");
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "cat",
+            "-i",
+            "echo",
+            "-o",
+            "echo 3",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--param",
+            "1..3,3",
+            "--slowest",
+            "4",
+            "--corpus",
+            corpus.to_str().unwrap(),
+        ]);
+        cmd.assert().success();
+        let manifest = std::fs::read_to_string(corpus.join("corpus.tsv")).unwrap();
+        assert_eq!(manifest.lines().count(), 3);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["hack", "replay", "-c", "echo 3", "--corpus"])
+            .arg(&corpus);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Changed: WA -> AC"))
+            .stderr(predicate::str::contains("[Replay] End (AC:3,"))
+            .stderr(predicate::str::contains("Regressions: 0"));
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["hack", "replay", "-c", "echo 4", "--corpus"])
+            .arg(&corpus);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Regression: AC -> WA"))
+            .stderr(predicate::str::contains("Regressions: 1"));
+    });
+}
+
+#[test]
+fn hack_batch_corpus_records_termination() {
+    with_tempdir(|tempdir| {
+        let corpus = tempdir.path().join("corpus");
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack", "batch", "-c", "false", "-i", "echo", "-o", "echo", "-d",
+        ])
+        .arg(tempdir.path())
+        .arg("--corpus")
+        .arg(&corpus);
+        cmd.assert().success();
+        let manifest = std::fs::read_to_string(corpus.join("corpus.tsv")).unwrap();
+        assert!(manifest.trim_end().ends_with("\tRE\texit 1"));
+    });
+}
+
+#[test]
+fn hack_batch_concurrent_sessions() {
+    with_tempdir(|tempdir| {
+        let spawn = || {
+            Command::cargo_bin(CRATE_NAME)
+                .unwrap()
+                .args([
+                    "hack",
+                    "batch",
+                    "-c",
+                    "cat",
+                    "-i",
+                    "echo a",
+                    "-o",
+                    "echo b",
+                    "-d",
+                    tempdir.path().to_str().unwrap(),
+                ])
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .unwrap()
+        };
+        let children = [spawn(), spawn(), spawn()];
+        for mut child in children {
+            assert!(child.wait().unwrap().success());
+        }
+        for no in 0..3 {
+            let input = tempdir.path().join(format!("Generated_{}.in", no));
+            assert_eq!(std::fs::read_to_string(input).unwrap(), "a\n");
+            let judge = tempdir.path().join(format!("Generated_{}.judge.txt", no));
+            assert!(judge.exists());
+        }
+    });
+}
+
+const GRAPH_SPEC: &str = r#"
+[[item]]
+type = "int"
+name = "n"
+min = "N"
+max = "N"
+end = " "
+
+[[item]]
+type = "int"
+name = "m"
+min = "n"
+max = "n"
+
+[[item]]
+type = "graph"
+n = "n"
+m = "m"
+connected = true
+
+[[item]]
+type = "array"
+len = "n"
+min = 1
+max = 5
+distinct = true
+"#;
+
+fn hack_with_spec(dir: &std::path::Path, spec: &std::path::Path) -> assert_cmd::assert::Assert {
+    let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+    cmd.args([
+        "hack", "batch", "-c", "echo", "-o", "cat", "--param", "N=5", "--spec",
+    ])
+    .arg(spec)
+    .arg("-d")
+    .arg(dir);
+    cmd.assert()
+}
+
+#[test]
+fn hack_batch_spec_generates_input() {
+    with_tempdir(|tempdir| {
+        let spec = tempdir.path().join("spec.toml");
+        write_sync(&spec, GRAPH_SPEC, true);
+        let dirs = [tempdir.path().join("1"), tempdir.path().join("2")];
+        for dir in &dirs {
+            std::fs::create_dir(dir).unwrap();
+            hack_with_spec(dir, &spec)
+                .success()
+                .stderr(predicate::str::contains(
+                    "[Batch Hack][Trial 1] Params: N=5 seed=1",
+                ));
+        }
+
+        let read =
+            |dir: &std::path::Path, name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        let input = read(&dirs[0], "Generated_0.in");
+        let lines = input.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "5 5");
+        let mut edges = lines[1..6]
+            .iter()
+            .map(|line| {
+                let (u, v) = line.split_once(' ').unwrap();
+                let (u, v) = (u.parse::<u32>().unwrap(), v.parse::<u32>().unwrap());
+                assert!(u != v && (1..=5).contains(&u) && (1..=5).contains(&v));
+                (u.min(v), u.max(v))
+            })
+            .collect::<Vec<_>>();
+        edges.sort();
+        edges.dedup();
+        assert_eq!(edges.len(), 5);
+        let mut array = lines[6].split(' ').collect::<Vec<_>>();
+        array.sort();
+        assert_eq!(array, ["1", "2", "3", "4", "5"]);
+        // The same seed gives the same input.
+        assert_eq!(read(&dirs[1], "Generated_0.in"), input);
+        assert_eq!(read(&dirs[0], "Generated_0.param.txt"), "N=5 seed=1\n");
+    });
+}
+
+#[test]
+fn hack_batch_spec_invalid() {
+    with_tempdir(|tempdir| {
+        let spec = tempdir.path().join("spec.toml");
+        write_sync(
+            &spec,
+            "[[item]]\ntype = \"int\"\nmin = 1\nmax = \"M\"\n",
+            true,
+        );
+        hack_with_spec(tempdir.path(), &spec)
+            .failure()
+            .stderr(predicate::str::contains("`M` is neither defined"));
+
+        write_sync(&spec, "[[item]]\ntype = \"int\"\nmin = 1\nmxa = 2\n", true);
+        hack_with_spec(tempdir.path(), &spec)
+            .failure()
+            .stderr(predicate::str::contains("Cannot parse"));
+
+        write_sync(
+            &spec,
+            "[[item]]\ntype = \"array\"\nlen = 3\nmin = 1\nmax = 5\n\
+             distinct = true\ndistribution = \"sorted\"\n",
+            true,
+        );
+        hack_with_spec(tempdir.path(), &spec)
+            .failure()
+            .stderr(predicate::str::contains(
+                "`distinct` cannot be combined with `distribution`",
+            ));
+
+        write_sync(
+            &spec,
+            "[[item]]\ntype = \"string\"\nlen = 3\nalphabet = \"\"\n",
+            true,
+        );
+        hack_with_spec(tempdir.path(), &spec)
+            .failure()
+            .stderr(predicate::str::contains("`alphabet` of a string is empty"));
+
+        write_sync(&spec, "[[item]]\ntype = \"permutation\"\nlen = -1\n", true);
+        hack_with_spec(tempdir.path(), &spec)
+            .failure()
+            .stderr(predicate::str::contains("Negative length `len` = -1"));
+
+        write_sync(&spec, "[[item]]\ntype = \"int\"\nname = \"K\"\nmin = -3\nmax = -3\n\n[[item]]\ntype = \"array\"\nlen = \"K\"\nmin = 1\nmax = 5\n", true);
+        hack_with_spec(tempdir.path(), &spec)
+            .failure()
+            .stderr(predicate::str::contains("Negative length `len` = -3"));
+
+        write_sync(&spec, "items = []\n", true);
+        hack_with_spec(tempdir.path(), &spec)
+            .failure()
+            .stderr(predicate::str::contains("Cannot parse"));
+    });
+}
+
+#[test]
+fn hack_batch_spec_tree_shape() {
+    with_tempdir(|tempdir| {
+        let spec = tempdir.path().join("spec.toml");
+        write_sync(
+            &spec,
+            "[[item]]\ntype = \"tree\"\nn = \"N\"\nshape = \"star\"\n",
+            true,
+        );
+        hack_with_spec(tempdir.path(), &spec).success();
+
+        let input = std::fs::read_to_string(tempdir.path().join("Generated_0.in")).unwrap();
+        let mut degree = [0; 6];
+        for line in input.lines() {
+            let (u, v) = line.split_once(' ').unwrap();
+            degree[u.parse::<usize>().unwrap()] += 1;
+            degree[v.parse::<usize>().unwrap()] += 1;
+        }
+        assert_eq!(degree.iter().max(), Some(&4));
+    });
+}
+
+#[test]
+fn hack_batch_corpus_records_binary_input() {
+    with_tempdir(|tempdir| {
+        let corpus = tempdir.path().join("corpus");
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "false",
+            "-i",
+            "printf \\377",
+            "-o",
+            "echo",
+            "-d",
+        ])
+        .arg(tempdir.path())
+        .arg("--corpus")
+        .arg(&corpus);
+        cmd.assert().success();
+        let manifest = std::fs::read_to_string(corpus.join("corpus.tsv")).unwrap();
+        let name = manifest.split('\t').next().unwrap();
+        let input = std::fs::read(corpus.join(format!("{}.in", name))).unwrap();
+        assert_eq!(input, b"\xff");
+    });
+}
@@ -161,3 +161,37 @@ fn hack_reactive_empty_directory() {
             .stderr(predicate::str::contains("Failed to spawn"));
     });
 }
+
+#[test]
+fn hack_replay_reactive() {
+    with_tempdir(|tempdir| {
+        let corpus = tempdir.path().join("corpus");
+        write_sync(corpus.join("0.in"), "hello\n", true);
+        write_sync(corpus.join("corpus.tsv"), "0\tAC\n", true);
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(
+            &judge,
+            "read x < \"$1\"\necho \"$x\"\nread y\n[ \"$x\" = \"$y\" ]\n",
+            true,
+        );
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["hack", "replay", "-c", "cat", "--reactive", "-j"])
+            .arg(format!("sh {}", judge.display()))
+            .arg("--corpus")
+            .arg(&corpus);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Replay] End (AC:1,"));
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["hack", "rp", "-c", "echo wrong", "--reactive", "-j"])
+            .arg(format!("sh {}", judge.display()))
+            .arg("--corpus")
+            .arg(&corpus);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("[Replay][0] Regression: AC -> WA"))
+            .stderr(predicate::str::contains("1 regression(s) found."));
+    });
+}
@@ -161,3 +161,34 @@ fn hack_special_empty_directory() {
             .stderr(predicate::str::contains("Failed to spawn"));
     });
 }
+
+#[test]
+fn hack_special_reference_answer() {
+    with_tempdir(|tempdir| {
+        // Accepts only if the output equals the answer.
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(&judge, "[ $# -eq 3 ] && cmp -s \"$2\" \"$3\"\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "special",
+            "-c",
+            "echo 1",
+            "-i",
+            "echo",
+            "-o",
+            "echo 2",
+            "-j",
+            &format!("sh {}", judge.display()),
+            "-d",
+            tempdir.path().to_str().unwrap(),
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Special Hack][Trial 1] Start"))
+            .stderr(predicate::str::contains("[Special Hack][Trial 1] End: WA"));
+        let answer = std::fs::read_to_string(tempdir.path().join("Generated_0.out")).unwrap();
+        assert_eq!(answer, "2\n");
+    });
+}
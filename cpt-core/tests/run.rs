@@ -0,0 +1,108 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+#[test]
+fn run_prints_output_and_statistics() {
+    with_tempdir(|tempdir| {
+        let input = tempdir.path().join("sample.in");
+        write_sync(&input, "hello", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["run", "-c", "cat", "-i", input.to_str().unwrap()]);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("hello"))
+            .stderr(predicate::str::contains("Time:"))
+            .stderr(predicate::str::contains("Exit:"));
+    });
+}
+
+#[test]
+fn run_compare_with_expect() {
+    with_tempdir(|tempdir| {
+        let input = tempdir.path().join("sample.in");
+        let expect = tempdir.path().join("sample.out");
+        write_sync(&input, "hello", true);
+        write_sync(&expect, "world", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "run",
+            "-c",
+            "cat",
+            "-i",
+            input.to_str().unwrap(),
+            "-e",
+            expect.to_str().unwrap(),
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("WA"));
+    });
+}
+
+#[test]
+fn run_input_not_found() {
+    let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+    cmd.args(["run", "-c", "cat", "-i", "/nonexistent/input.in"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("is not found"));
+}
+
+#[test]
+fn run_command_exec_failed() {
+    with_tempdir(|tempdir| {
+        let input = tempdir.path().join("sample.in");
+        write_sync(&input, "hello", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "r",
+            "-c",
+            "noexistent_command",
+            "-i",
+            input.to_str().unwrap(),
+        ]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Failed to spawn"));
+    });
+}
+
+#[test]
+fn run_writes_raw_output() {
+    with_tempdir(|tempdir| {
+        let input = tempdir.path().join("sample.in");
+        write_sync(&input, b"\xff\xfe\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["run", "-c", "cat", "-i"]).arg(&input);
+        cmd.assert().success().stdout(&b"\xff\xfe\n"[..]);
+    });
+}
+
+#[test]
+fn run_scales_time_limit() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("cpt.toml"), "tl_scale = 0.2\n", true);
+        write_sync(tempdir.path().join("sample.in"), "0.5\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["run", "-c", "xargs sleep", "-t", "1000", "-i", "sample.in"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Limits] Time limit: 1000ms -> 200ms (x0.20)",
+            ))
+            .stderr(predicate::str::contains("[Run] TLE"));
+    });
+}
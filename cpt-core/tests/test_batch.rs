@@ -98,3 +98,204 @@ fn testcase_pass() {
         cmd.assert().success();
     });
 }
+
+#[test]
+fn testcase_runtime_error_reports_exit_code() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        prepare(tempdir.path(), "RE_1", "abc", Some("abc"));
+
+        cmd.args([
+            "test",
+            "batch",
+            "-c",
+            "false",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("RE (exit 1)"));
+    });
+}
+
+#[test]
+fn testcase_output_limit_exceeded() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        prepare(tempdir.path(), "OLE_1", "abc", Some("abc"));
+
+        cmd.args([
+            "test",
+            "batch",
+            "-c",
+            "yes",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--output-limit",
+            "1",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("OLE"));
+        let actual = std::fs::metadata(tempdir.path().join("OLE_1.actual.txt")).unwrap();
+        assert_eq!(actual.len(), 1 << 20);
+    });
+}
+
+#[test]
+fn testcase_missing_expected_output() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        prepare(tempdir.path(), "AC_input_only", "hoge", None);
+
+        cmd.args([
+            "test",
+            "batch",
+            "-c",
+            "cat",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+        ]);
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Batch Test][AC_input_only] No expected output",
+        ));
+    });
+}
+
+#[test]
+fn testcase_repeat_detects_flaky_output() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        prepare(tempdir.path(), "1", "", None);
+
+        cmd.args(["test", "batch", "-c", "date +%N", "--repeat", "3", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Batch Test][1] Flaky: the output changed (Run 2)",
+            ))
+            .stderr(predicate::str::contains("Flaky: 1"));
+    });
+}
+
+#[test]
+fn testcase_repeat_detects_flaky_verdict() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        prepare(tempdir.path(), "1", "abc", Some("abc"));
+        prepare(tempdir.path(), "2", "def", Some("def"));
+        // Fails on every other run.
+        let marker = tempdir.path().join("marker");
+        let script = tempdir.path().join("flaky.sh");
+        write_sync(
+            &script,
+            format!(
+                "if [ -e {0} ]; then rm {0}; exit 1; else touch {0}; cat; fi\n",
+                marker.display()
+            ),
+            true,
+        );
+
+        cmd.args(["test", "batch", "--repeat", "2", "-c"])
+            .arg(format!("sh {}", script.display()))
+            .arg("-d")
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Flaky: AC -> RE (Run 2)"))
+            .stderr(predicate::str::contains("- abc"))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:0,"))
+            .stderr(predicate::str::contains("Flaky: 2"));
+    });
+}
+
+#[test]
+fn testcase_memory_limit_exceeded() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        prepare(tempdir.path(), "MLE_1", "abc", Some("abc"));
+
+        cmd.args([
+            "test",
+            "batch",
+            "-c",
+            "cat",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "-m",
+            "0",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("MLE"));
+    });
+}
+
+#[test]
+fn test_batch_watch_reruns_on_change() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    with_tempdir(|tempdir| {
+        let casedir = tempdir.path().join("cases");
+        write_sync(casedir.join("1.in"), "abc", true);
+        write_sync(casedir.join("1.out"), "abd", true);
+        // Hangs on the first run, recording its pid.
+        let pidfile = tempdir.path().join("pid");
+        let solution = tempdir.path().join("solution.sh");
+        write_sync(
+            &solution,
+            format!(
+                "[ -e {0} ] && exec cat\necho $$ > {0}\nexec sleep 1000\n",
+                pidfile.display()
+            ),
+            true,
+        );
+
+        let mut child = Command::cargo_bin(CRATE_NAME)
+            .unwrap()
+            .args(["test", "batch", "-c"])
+            .arg(format!("sh {}", solution.display()))
+            .arg("-d")
+            .arg(&casedir)
+            .arg("--watch")
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let (tx, rx) = mpsc::channel();
+        let stderr = BufReader::new(child.stderr.take().unwrap());
+        std::thread::spawn(move || {
+            for line in stderr.lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let wait_for = |pattern: &str| loop {
+            let line = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+            if line.contains(pattern) {
+                break;
+            }
+        };
+
+        wait_for("[Judge][1] Start");
+        while !pidfile.exists() {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+        let pid = std::fs::read_to_string(&pidfile).unwrap();
+        write_sync(casedir.join("1.out"), "abc", true);
+        wait_for("[Watch] Cancelled the running test");
+        wait_for("[Watch] Restart");
+        wait_for("[Batch Test] End (AC:1,");
+        // The solution of the cancelled test is gone (or a zombie left to be reaped).
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()));
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(stat.map_or(true, |stat| stat.contains(") Z ")));
+    });
+}
@@ -177,3 +177,108 @@ fn test_reactive_input_only_case() {
         cmd.assert().success();
     });
 }
+
+#[test]
+fn test_reactive_output_limit_exceeded() {
+    with_tempdir(|tempdir| {
+        prepare_testcase(tempdir.path(), "OLE_1", "hello", "hello");
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "test",
+            "reactive",
+            "-c",
+            "yes",
+            "-j",
+            "cat",
+            "-d",
+            tempdir.path().to_str().unwrap(),
+            "--output-limit",
+            "1",
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("OLE"));
+    });
+}
+
+#[test]
+fn test_reactive_runtime_error() {
+    with_tempdir(|tempdir| {
+        prepare_testcase(tempdir.path(), "RE_1", "hello", "hello");
+        let program = tempdir.path().join("program.sh");
+        write_sync(&program, "kill -SEGV $$\n", true);
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(&judge, "read x\nexit 1\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["test", "reactive", "-c"])
+            .arg(format!("sh {}", program.display()))
+            .arg("-j")
+            .arg(format!("sh {}", judge.display()))
+            .arg("-d")
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Judge][RE_1] RE (SIGSEGV)"));
+    });
+}
+
+#[test]
+fn test_reactive_wrong_answer() {
+    with_tempdir(|tempdir| {
+        prepare_testcase(tempdir.path(), "WA_1", "hello", "hello");
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(&judge, "echo query\nread x\nexit 1\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["test", "reactive", "-c", "cat", "-j"])
+            .arg(format!("sh {}", judge.display()))
+            .arg("-d")
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Judge][WA_1] WA"));
+    });
+}
+
+#[test]
+fn test_reactive_output_limit_counts_bytes() {
+    with_tempdir(|tempdir| {
+        prepare_testcase(tempdir.path(), "OLE_1", "hello", "hello");
+        // A single line longer than the limit, without a newline.
+        let program = tempdir.path().join("program.sh");
+        write_sync(&program, "head -c 2000000 /dev/zero\nsleep 10\n", true);
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(&judge, "cat > /dev/null\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["test", "reactive", "-c"])
+            .arg(format!("sh {}", program.display()))
+            .arg("-j")
+            .arg(format!("sh {}", judge.display()))
+            .args(["-t", "5000", "--output-limit", "1", "-d"])
+            .arg(tempdir.path());
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Judge][OLE_1] OLE (1048576bytes, ",
+        ));
+    });
+}
+
+#[test]
+fn test_reactive_memory_limit_exceeded() {
+    with_tempdir(|tempdir| {
+        prepare_testcase(tempdir.path(), "MLE_1", "hello", "hello");
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(&judge, "echo query\nread x\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["test", "reactive", "-c", "cat", "-j"])
+            .arg(format!("sh {}", judge.display()))
+            .args(["-m", "0", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Judge][MLE_1] MLE ("));
+    });
+}
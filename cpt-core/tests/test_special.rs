@@ -177,3 +177,60 @@ fn test_special_input_only_case() {
         cmd.assert().success();
     });
 }
+
+#[test]
+fn test_special_reference_answer() {
+    with_tempdir(|tempdir| {
+        let casedir = tempdir.path().join("test");
+        std::fs::create_dir(&casedir).unwrap();
+        // The reference solution takes the place of the stale expected output.
+        prepare_testcase(&casedir, "1", "hello\n", "stale\n");
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(&judge, "[ $# -eq 3 ] && cmp -s \"$2\" \"$3\"\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "test",
+            "special",
+            "-c",
+            "cat",
+            "-o",
+            "cat",
+            "-j",
+            &format!("sh {}", judge.display()),
+            "-d",
+            casedir.to_str().unwrap(),
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Special Test] End (AC:1,"));
+        assert!(!casedir.join("1.answer.txt").exists());
+    });
+}
+
+#[test]
+fn test_special_no_answer_without_reference() {
+    with_tempdir(|tempdir| {
+        let casedir = tempdir.path().join("test");
+        std::fs::create_dir(&casedir).unwrap();
+        prepare_testcase(&casedir, "1", "hello\n", "hello\n");
+        // The `.out` file is not given to the judge.
+        let judge = tempdir.path().join("judge.sh");
+        write_sync(&judge, "[ $# -eq 2 ]\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "test",
+            "special",
+            "-c",
+            "cat",
+            "-j",
+            &format!("sh {}", judge.display()),
+            "-d",
+            casedir.to_str().unwrap(),
+        ]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Special Test] End (AC:1,"));
+    });
+}
@@ -0,0 +1,202 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+#[test]
+fn tl_scale_option() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("TLE_1.in"), "0.5\n", true);
+        write_sync(tempdir.path().join("TLE_1.out"), "", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args([
+                "test",
+                "--tl-scale",
+                "0.2",
+                "batch",
+                "-c",
+                "xargs sleep",
+                "-t",
+                "1000",
+                "-d",
+            ])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Limits] Time limit: 1000ms -> 200ms (x0.20)",
+            ))
+            .stderr(predicate::str::contains("ms/200ms)"))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:0,"));
+    });
+}
+
+#[test]
+fn language_tl_scale() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("cpt.toml"),
+            "tl_scale = 2.0\n\n[languages.shell]\nextensions = [\"sh\"]\nrun = \"sh {source}\"\ntl_scale = 1.5\n",
+            true,
+        );
+        write_sync(tempdir.path().join("main.sh"), "cat\n", true);
+        write_sync(tempdir.path().join("cases/AC_1.in"), "abc", true);
+        write_sync(tempdir.path().join("cases/AC_1.out"), "abc", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args([
+                "test", "batch", "-s", "main.sh", "-t", "1000", "-d", "cases",
+            ]);
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Limits] Time limit: 1000ms -> 3000ms (x3.00)",
+        ));
+
+        // Without `-s`, the language is unknown.
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.current_dir(tempdir.path())
+            .env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["test", "batch", "-c", "cat", "-t", "1000", "-d", "cases"]);
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Limits] Time limit: 1000ms -> 2000ms (x2.00)",
+        ));
+    });
+}
+
+#[test]
+fn calibrate_stores_factor() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("AC_1.in"), "0.3\n", true);
+        write_sync(tempdir.path().join("AC_1.out"), "", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["calibrate", "--factor", "2.5"]);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Calibrate] Machine factor: 2.50"));
+        assert!(tempdir.path().join("config/cpt/calibration.toml").is_file());
+
+        // 200ms is too short without the factor.
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["test", "batch", "-c", "xargs sleep", "-t", "200", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Limits] Time limit: 200ms -> 500ms (x2.50)",
+            ))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+    });
+}
+
+#[test]
+fn calibrate_measures_factor() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["calibrate", "--repeat", "1"]);
+        let calibration = tempdir.path().join("config/cpt/calibration.toml");
+        // The binary under test is built with the same profile as this test.
+        if cfg!(debug_assertions) {
+            cmd.assert()
+                .failure()
+                .stderr(predicate::str::contains("debug build"));
+            assert!(!calibration.exists());
+        } else {
+            cmd.assert()
+                .success()
+                .stderr(predicate::str::contains("[Calibrate] Run 1: "))
+                .stderr(predicate::str::contains("[Calibrate] Machine factor: "));
+            let content = std::fs::read_to_string(&calibration).unwrap();
+            assert!(content.starts_with("machine_factor = "));
+        }
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["calibrate", "--factor", "0"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("must be positive"));
+    });
+}
+
+#[test]
+fn broken_calibration_is_ignored() {
+    with_tempdir(|tempdir| {
+        write_sync(
+            tempdir.path().join("config/cpt/calibration.toml"),
+            "broken",
+            true,
+        );
+        write_sync(tempdir.path().join("AC_1.in"), "abc", true);
+        write_sync(tempdir.path().join("AC_1.out"), "abc", true);
+
+        // Loaded only by the commands which need the time limit.
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["validate", "--validator", "true", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Calibration]").not());
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["test", "batch", "-c", "cat", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Calibration] Cannot parse"))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+
+        write_sync(
+            tempdir.path().join("config/cpt/calibration.toml"),
+            "machine_factor = 0.0\n",
+            true,
+        );
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.env("XDG_CONFIG_HOME", tempdir.path().join("config"))
+            .args(["test", "batch", "-c", "cat", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "[Calibration] Invalid machine factor",
+            ))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+    });
+}
+
+#[test]
+fn invalid_tl_scale_is_rejected() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["test", "--tl-scale", "0", "batch", "-c", "cat", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("is not a positive finite number"));
+
+        for config in [
+            "tl_scale = -1.0\n",
+            "[languages.shell]\nextensions = [\"sh\"]\nrun = \"sh {source}\"\ntl_scale = nan\n",
+        ] {
+            write_sync(tempdir.path().join("cpt.toml"), config, true);
+            let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+            cmd.current_dir(tempdir.path())
+                .args(["test", "batch", "-c", "cat", "-d", "."]);
+            cmd.assert()
+                .failure()
+                .stderr(predicate::str::contains("is not a positive finite number"));
+        }
+    });
+}
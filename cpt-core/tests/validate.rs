@@ -0,0 +1,84 @@
+pub mod common;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+use crate::common::{with_tempdir, write_sync, CRATE_NAME};
+
+const VALIDATOR: &str = "grep -qE ^[0-9]+$";
+
+#[test]
+fn validate_reports_invalid_inputs() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "123\n", true);
+        write_sync(tempdir.path().join("2.in"), "abc\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["validate", "--validator", VALIDATOR, "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("[Validate][2] Invalid"))
+            .stderr(predicate::str::contains(
+                "[Validate] End (Valid:1,Invalid:1)",
+            ));
+    });
+}
+
+#[test]
+fn validate_all_valid() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "123\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["v", "--validator", VALIDATOR, "-d"])
+            .arg(tempdir.path());
+        cmd.assert().success();
+    });
+}
+
+#[test]
+fn validate_before_test() {
+    with_tempdir(|tempdir| {
+        write_sync(tempdir.path().join("1.in"), "abc\n", true);
+        write_sync(tempdir.path().join("1.out"), "abc\n", true);
+
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args(["test", "--validator", VALIDATOR, "batch", "-c", "cat", "-d"])
+            .arg(tempdir.path());
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("[Validate][1] Invalid"))
+            .stderr(predicate::str::contains("[Batch Test] End (AC:1,"));
+    });
+}
+
+#[test]
+fn validate_skips_invalid_hack_input() {
+    with_tempdir(|tempdir| {
+        let mut cmd = Command::cargo_bin(CRATE_NAME).unwrap();
+        cmd.args([
+            "hack",
+            "batch",
+            "-c",
+            "cat",
+            "-i",
+            "echo",
+            "-o",
+            "echo 1",
+            "--param",
+            "a,5",
+            "--validator",
+            VALIDATOR,
+            "-d",
+        ])
+        .arg(tempdir.path());
+        cmd.assert().success().stderr(predicate::str::contains(
+            "[Batch Hack][Trial 1] Invalid input",
+        ));
+        let input = std::fs::read_to_string(tempdir.path().join("Generated_0.in")).unwrap();
+        assert_eq!(input, "5\n");
+    });
+}
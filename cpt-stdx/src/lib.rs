@@ -2,4 +2,5 @@ pub mod error;
 pub mod fs;
 pub mod path;
 pub mod process;
+pub mod random;
 pub mod tempfile;
@@ -14,6 +14,26 @@ pub enum Error {
     /// Program exceeded the specified timeout limit.
     #[error("Program timeout(command:`$ {0}`, elapsed:{2}ms/{1}ms")]
     ProgramTimeout(Command, u64, u64),
+    /// Program output exceeded the specified limit (in bytes).
+    #[error("Program output limit exceeded(command:`$ {0}`, limit:{1}bytes).")]
+    ProgramOutputLimitExceeded(Command, u64),
+    /// Failed to write the program output to its capture.
+    #[error("Failed to capture the output(command:`$ {0}`).")]
+    CaptureFailed(Command, #[source] std::io::Error),
+}
+
+/// Returns the async runtime shared by all process executions.
+///
+/// The runtime is created on the first call and reused afterwards,
+/// so that running thousands of programs does not build thousands of runtimes.
+pub fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    })
 }
 
 /// Represents the execution status of a command.
@@ -35,11 +55,14 @@ impl Status {
     ///
     /// A Status instance with appropriate summary and details
     pub fn from(output: std::process::Output) -> Self {
-        let start = tokio::time::Instant::now();
         let detail = StatusDetail {
             stdout: String::from_utf8_lossy(&output.stdout).into(),
             stderr: String::from_utf8_lossy(&output.stderr).into(),
-            elapsed_ms: (tokio::time::Instant::now() - start).as_millis() as u64,
+            elapsed_ms: 0,
+            memory_kb: None,
+            cpu_ms: None,
+            exit_code: output.status.code(),
+            signal: sys::signal(output.status),
         };
         Status {
             summary: if output.status.success() {
@@ -61,6 +84,8 @@ pub enum StatusSummary {
     Aborted,
     /// Command exceeded the specified timeout.
     Timeout,
+    /// Command output exceeded the specified limit, and was stopped.
+    OutputLimitExceeded,
 }
 
 /// Detailed information about command execution.
@@ -75,6 +100,91 @@ pub struct StatusDetail {
     pub stderr: String,
     /// Execution time in milliseconds.
     pub elapsed_ms: u64,
+    /// Peak resident set size in kilobytes.
+    ///
+    /// `None` if the platform does not report resource usage of child processes.
+    pub memory_kb: Option<u64>,
+    /// User and system CPU time in milliseconds.
+    ///
+    /// `None` if the platform does not report resource usage of child processes.
+    pub cpu_ms: Option<u64>,
+    /// Exit code of the command.
+    ///
+    /// `None` if the command was terminated by a signal or killed because of the timeout.
+    pub exit_code: Option<i32>,
+    /// Signal number that terminated the command (Unix only).
+    ///
+    /// `None` if the command exited normally or was killed because of the timeout.
+    pub signal: Option<i32>,
+}
+impl StatusDetail {
+    /// How the command terminated.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the command was killed because of the timeout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cpt_stdx::process::{StatusDetail, Termination};
+    ///
+    /// let detail = StatusDetail {
+    ///     stdout: "".into(),
+    ///     stderr: "".into(),
+    ///     elapsed_ms: 0,
+    ///     memory_kb: None,
+    ///     cpu_ms: None,
+    ///     exit_code: Some(1),
+    ///     signal: None,
+    /// };
+    /// assert_eq!(detail.termination(), Some(Termination::Exit(1)));
+    /// assert_eq!(detail.termination().unwrap().to_string(), "exit 1");
+    /// ```
+    pub fn termination(&self) -> Option<Termination> {
+        match (self.signal, self.exit_code) {
+            (Some(signal), _) => Some(Termination::Signal(signal)),
+            (None, Some(code)) => Some(Termination::Exit(code)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// How a command terminated, displayed as e.g. `SIGSEGV` or `exit 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// Exited with the code.
+    Exit(i32),
+    /// Terminated by the signal (Unix only).
+    Signal(i32),
+}
+impl std::fmt::Display for Termination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Termination::Exit(code) => write!(f, "exit {}", code),
+            Termination::Signal(signal) => write!(f, "{}", signal_name(*signal)),
+        }
+    }
+}
+
+/// Returns the conventional name of a signal number (e.g. `SIGSEGV` for 11 on Linux).
+///
+/// Unknown signals are formatted as `SIG<number>`.
+///
+/// # Example
+///
+/// ```rust
+/// use cpt_stdx::process::signal_name;
+///
+/// #[cfg(unix)]
+/// assert_eq!(signal_name(9), "SIGKILL");
+/// assert_eq!(signal_name(99), "SIG99");
+/// ```
+pub fn signal_name(signal: i32) -> String {
+    sys::SIGNAL_NAMES
+        .iter()
+        .find(|(number, _)| *number == signal)
+        .map_or_else(|| format!("SIG{}", signal), |(_, name)| name.to_string())
 }
 
 /// Represents a command to be executed with its program name and arguments.
@@ -122,6 +232,13 @@ impl Command {
     }
     /// Spawns the command as a child process with the specified I/O redirection.
     ///
+    /// On Unix, the child is placed in its own process group (unless its stdin is a terminal),
+    /// and the whole group (including grandchildren started by e.g. `sh -c`) is killed
+    /// when the returned `Child` is dropped.
+    ///
+    /// It must be called from within the context of a Tokio runtime,
+    /// since the child is waited for by a blocking task from the start.
+    ///
     /// # Arguments
     ///
     /// * `redirect` - I/O redirection configuration for stdin, stdout, and stderr
@@ -145,19 +262,46 @@ impl Command {
     /// };
     /// // let child = cmd.spawn(redirect).expect("Failed to spawn");
     /// ```
-    pub fn spawn(&self, redirect: IoRedirection) -> Result<tokio::process::Child, Error> {
-        let mut command = tokio::process::Command::new(&self.program);
-        command
-            .kill_on_drop(true)
+    pub fn spawn(&self, redirect: IoRedirection) -> Result<Child, Error> {
+        let mut command = std::process::Command::new(&self.program);
+        sys::set_group(&mut command);
+        let mut child = command
             .args(&self.args)
             .stdin(redirect.stdin)
             .stdout(redirect.stdout)
             .stderr(redirect.stderr)
             .spawn()
-            .map_err(|_| Error::SpawnFailed(self.to_owned()))
+            .map_err(|_| Error::SpawnFailed(self.to_owned()))?;
+        let start = std::time::Instant::now();
+        let group = ProcessGroup::new(child.id());
+        let stdin = child.stdin.take().map(tokio::process::ChildStdin::from_std);
+        let stdout = child
+            .stdout
+            .take()
+            .map(tokio::process::ChildStdout::from_std);
+        let stderr = child
+            .stderr
+            .take()
+            .map(tokio::process::ChildStderr::from_std);
+        let waiter = tokio::task::spawn_blocking(move || {
+            let (exit_status, usage) = sys::wait(child);
+            (exit_status, usage, start.elapsed().as_millis() as u64)
+        });
+        let pipe_error = |_| Error::SpawnFailed(self.to_owned());
+        Ok(Child {
+            stdin: stdin.transpose().map_err(pipe_error)?,
+            stdout: stdout.transpose().map_err(pipe_error)?,
+            stderr: stderr.transpose().map_err(pipe_error)?,
+            waiter,
+            waited: None,
+            group,
+        })
     }
     /// Executes the command and waits for it to complete.
     ///
+    /// This is a blocking wrapper of [`Command::exec_async`] on the shared [`runtime`].
+    /// It must not be called from within an async context.
+    ///
     /// # Arguments
     ///
     /// * `redirect` - I/O redirection configuration
@@ -191,68 +335,758 @@ impl Command {
         timeout_ms: u64,
         ensure_success: bool,
     ) -> Result<Status, Error> {
-        let res = tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .unwrap()
-            .block_on(async {
-                match tokio::time::timeout(
-                    tokio::time::Duration::from_millis(timeout_ms) * 2,
-                    async {
-                        Ok(Status::from(
-                            self.spawn(redirect)?.wait_with_output().await.unwrap(),
-                        ))
-                    },
-                )
+        runtime().block_on(self.exec_async(redirect, timeout_ms, ensure_success))
+    }
+    /// Executes the command and waits for it to complete asynchronously.
+    ///
+    /// Dropping the returned future kills the whole process group of the command.
+    /// See [`Command::exec`] for the arguments and the return value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cpt_stdx::process::{runtime, Command, IoRedirection};
+    /// use std::process::Stdio;
+    ///
+    /// let cmd = Command::new("echo", vec!["hello"]);
+    /// let redirect = IoRedirection {
+    ///     stdin: Stdio::null(),
+    ///     stdout: Stdio::piped(),
+    ///     stderr: Stdio::piped(),
+    /// };
+    /// let status = runtime()
+    ///     .block_on(cmd.exec_async(redirect, 5000, false))
+    ///     .expect("Failed to execute");
+    /// ```
+    pub async fn exec_async(
+        &self,
+        redirect: IoRedirection,
+        timeout_ms: u64,
+        ensure_success: bool,
+    ) -> Result<Status, Error> {
+        let Captured {
+            status: Status { summary, detail },
+            stdout,
+            stderr,
+        } = self
+            .wait_timeout(
+                redirect.stdin,
+                (redirect.stdout, Capture::Memory { tail: None }),
+                (redirect.stderr, Capture::Memory { tail: None }),
+                None,
+                timeout_ms,
+            )
+            .await?;
+        let res = Status {
+            summary,
+            detail: StatusDetail {
+                stdout: String::from_utf8_lossy(&stdout).into(),
+                stderr: String::from_utf8_lossy(&stderr).into(),
+                ..detail
+            },
+        };
+        if ensure_success {
+            self.ensure_success(&res, res.detail.stderr.to_owned(), timeout_ms, None)?;
+        }
+        Ok(res)
+    }
+    /// Executes the command, streaming its output to the given captures.
+    ///
+    /// Unlike [`Command::exec`], the output is never converted into `String`s,
+    /// and the command is stopped as soon as its stdout or stderr exceeds
+    /// `CaptureRedirection::limit_bytes` (reported as `StatusSummary::OutputLimitExceeded`).
+    ///
+    /// # Arguments
+    ///
+    /// * `redirect` - Capture configuration
+    /// * `timeout_ms` - Maximum execution time in milliseconds
+    /// * `ensure_success` - If true, returns an error for non-zero exit codes, timeouts or output limit excess
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Captured)` - Execution status and the bytes captured in memory
+    /// * `Err(Error::SpawnFailed)` - If the process could not be spawned
+    /// * `Err(Error::ProgramAborted)` - If ensure_success is true and the program failed
+    /// * `Err(Error::ProgramTimeout)` - If ensure_success is true and the program timed out
+    /// * `Err(Error::ProgramOutputLimitExceeded)` - If ensure_success is true and the program output too much
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cpt_stdx::process::{Capture, CaptureRedirection, Command, StatusSummary};
+    /// use std::process::Stdio;
+    ///
+    /// let cmd = Command::new("yes", Vec::<String>::new());
+    /// let redirect = CaptureRedirection {
+    ///     stdin: Stdio::null(),
+    ///     stdout: Capture::Memory { tail: None },
+    ///     stderr: Capture::Null,
+    ///     limit_bytes: Some(1000),
+    /// };
+    /// let captured = cmd.exec_capture(redirect, 5000, false).expect("Failed to execute");
+    /// assert_eq!(captured.status.summary, StatusSummary::OutputLimitExceeded);
+    /// assert_eq!(captured.stdout.len(), 1000);
+    /// ```
+    pub fn exec_capture(
+        &self,
+        redirect: CaptureRedirection,
+        timeout_ms: u64,
+        ensure_success: bool,
+    ) -> Result<Captured, Error> {
+        runtime().block_on(self.exec_capture_async(redirect, timeout_ms, ensure_success))
+    }
+    /// Asynchronous version of [`Command::exec_capture`].
+    pub async fn exec_capture_async(
+        &self,
+        redirect: CaptureRedirection,
+        timeout_ms: u64,
+        ensure_success: bool,
+    ) -> Result<Captured, Error> {
+        let limit_bytes = redirect.limit_bytes;
+        let (stdout, stdout_capture) = redirect.stdout.into_stdio(limit_bytes.is_some());
+        let (stderr, stderr_capture) = redirect.stderr.into_stdio(limit_bytes.is_some());
+        let captured = self
+            .wait_timeout(
+                redirect.stdin,
+                (stdout, stdout_capture),
+                (stderr, stderr_capture),
+                limit_bytes,
+                timeout_ms,
+            )
+            .await?;
+        if ensure_success {
+            self.ensure_success(
+                &captured.status,
+                String::from_utf8_lossy(&captured.stderr).into(),
+                timeout_ms,
+                limit_bytes,
+            )?;
+        }
+        Ok(captured)
+    }
+    fn ensure_success(
+        &self,
+        status: &Status,
+        stderr: String,
+        timeout_ms: u64,
+        limit_bytes: Option<u64>,
+    ) -> Result<(), Error> {
+        match status.summary {
+            StatusSummary::Success => Ok(()),
+            StatusSummary::Aborted => Err(Error::ProgramAborted(self.to_owned(), stderr)),
+            StatusSummary::Timeout => Err(Error::ProgramTimeout(
+                self.to_owned(),
+                timeout_ms,
+                status.detail.elapsed_ms,
+            )),
+            StatusSummary::OutputLimitExceeded => Err(Error::ProgramOutputLimitExceeded(
+                self.to_owned(),
+                limit_bytes.unwrap_or_default(),
+            )),
+        }
+    }
+    /// Runs the command to completion, killing it after twice the `timeout_ms`.
+    ///
+    /// Unlike `tokio::process::Child::wait_with_output`, this also measures
+    /// the elapsed time and the resource usage of the child process.
+    /// Each output stream that is piped is pumped into its capture,
+    /// and the command is killed once a stream exceeds `limit_bytes`.
+    async fn wait_timeout(
+        &self,
+        stdin: std::process::Stdio,
+        (stdout, stdout_capture): (std::process::Stdio, Capture),
+        (stderr, stderr_capture): (std::process::Stdio, Capture),
+        limit_bytes: Option<u64>,
+        timeout_ms: u64,
+    ) -> Result<Captured, Error> {
+        use std::io::Read;
+
+        let mut command = std::process::Command::new(&self.program);
+        sys::set_group(&mut command);
+        let mut child = command
+            .args(&self.args)
+            .stdin(stdin)
+            .stdout(stdout)
+            .stderr(stderr)
+            .spawn()
+            .map_err(|_| Error::SpawnFailed(self.to_owned()))?;
+        let start = tokio::time::Instant::now();
+        // Dropping the guard (including cancellation of this future) kills the whole group.
+        let group = ProcessGroup::new(child.id());
+        let leader = group.0;
+        let pump = |pipe: Option<Box<dyn Read + Send>>, capture: Capture| {
+            tokio::task::spawn_blocking(move || match pipe {
+                Some(pipe) => pump(pipe, capture, limit_bytes, || leader.kill()),
+                None => Ok(Pumped::default()),
+            })
+        };
+        let stdout = pump(
+            child.stdout.take().map(|p| Box::new(p) as _),
+            stdout_capture,
+        );
+        let stderr = pump(
+            child.stderr.take().map(|p| Box::new(p) as _),
+            stderr_capture,
+        );
+
+        let mut waiter = tokio::task::spawn_blocking(move || sys::wait(child));
+        let hard_limit = tokio::time::Duration::from_millis(timeout_ms) * 2;
+        let (exit_status, usage, elapsed_ms) =
+            match tokio::time::timeout(hard_limit, &mut waiter).await {
+                Ok(waited) => {
+                    let (exit_status, usage) = waited.unwrap();
+                    let elapsed_ms = start.elapsed().as_millis() as u64;
+                    (Some(exit_status), usage, elapsed_ms)
+                }
+                Err(_) => {
+                    group.kill();
+                    let (_, usage) = waiter.await.unwrap();
+                    (None, usage, timeout_ms * 2)
+                }
+            };
+        // Leftover grandchildren may still hold the pipes open.
+        group.kill();
+        // A process which left the group may keep a pipe open forever, so a reader which does
+        // not finish within the grace period is detached (and ends when the pipe is closed).
+        let join = |reader: tokio::task::JoinHandle<std::io::Result<Pumped>>| async move {
+            tokio::time::timeout(READER_GRACE, reader)
                 .await
-                {
-                    Ok(Err(e)) => Err(e),
-                    Ok(Ok(Status { summary, detail })) => match summary {
-                        StatusSummary::Success => {
-                            if detail.elapsed_ms <= timeout_ms {
-                                Ok(Status {
-                                    summary: StatusSummary::Success,
-                                    detail,
-                                })
-                            } else {
-                                Ok(Status {
-                                    summary: StatusSummary::Timeout,
-                                    detail,
-                                })
-                            }
-                        }
-                        StatusSummary::Aborted => Ok(Status {
-                            summary: StatusSummary::Aborted,
-                            detail,
-                        }),
-                        _ => unreachable!(),
-                    },
-                    Err(_) => Ok(Status {
-                        summary: StatusSummary::Timeout,
-                        detail: StatusDetail {
-                            stdout: "".into(),
-                            stderr: "".into(),
-                            elapsed_ms: timeout_ms * 2,
-                        },
-                    }),
+                .map_or_else(|_| Ok(Pumped::default()), Result::unwrap)
+                .map_err(|e| Error::CaptureFailed(self.to_owned(), e))
+        };
+        let (stdout, stderr) = tokio::join!(join(stdout), join(stderr));
+        let (stdout, stderr) = (stdout?, stderr?);
+
+        let summary = if stdout.exceeded || stderr.exceeded {
+            StatusSummary::OutputLimitExceeded
+        } else {
+            match exit_status {
+                None => StatusSummary::Timeout,
+                Some(exit_status) if !exit_status.success() => StatusSummary::Aborted,
+                Some(_) if elapsed_ms <= timeout_ms => StatusSummary::Success,
+                Some(_) => StatusSummary::Timeout,
+            }
+        };
+        Ok(Captured {
+            status: Status {
+                summary,
+                detail: StatusDetail {
+                    stdout: "".into(),
+                    stderr: "".into(),
+                    elapsed_ms,
+                    memory_kb: usage.memory_kb,
+                    cpu_ms: usage.cpu_ms,
+                    exit_code: exit_status.and_then(|status| status.code()),
+                    signal: exit_status.and_then(sys::signal),
+                },
+            },
+            stdout: stdout.kept,
+            stderr: stderr.kept,
+        })
+    }
+}
+
+/// How long the output readers are waited for after the process group is killed.
+const READER_GRACE: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Result of [`pump`].
+#[derive(Debug, Default)]
+struct Pumped {
+    /// Bytes kept by `Capture::Memory`.
+    kept: Vec<u8>,
+    /// Whether more than the limit arrived.
+    exceeded: bool,
+}
+
+/// Copies everything from `pipe` into `capture`.
+///
+/// When more than `limit_bytes` bytes arrive, only the first `limit_bytes` bytes are kept,
+/// `kill` is called and the pipe is closed. `kill` is also called when writing to the capture fails.
+fn pump(
+    mut pipe: impl std::io::Read,
+    mut capture: Capture,
+    limit_bytes: Option<u64>,
+    kill: impl FnOnce(),
+) -> std::io::Result<Pumped> {
+    use std::io::Write;
+
+    let mut kept = Vec::new();
+    let mut total = 0u64;
+    let mut buf = vec![0u8; 1 << 16];
+    loop {
+        let mut len = match pipe.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        };
+        let exceeded = limit_bytes.is_some_and(|limit| total + len as u64 > limit);
+        if exceeded {
+            len = (limit_bytes.unwrap() - total) as usize;
+        }
+        total += len as u64;
+        match &mut capture {
+            Capture::Null => {}
+            Capture::Memory { tail } => {
+                kept.extend_from_slice(&buf[..len]);
+                // Trim lazily, so that retaining the tail stays linear.
+                if let Some(tail) = *tail {
+                    if kept.len() > tail.max(1 << 16) * 2 {
+                        kept.drain(..kept.len() - tail);
+                    }
                 }
-            })?;
-        if ensure_success {
-            match res.summary {
-                StatusSummary::Success => Ok(res),
-                StatusSummary::Aborted => {
-                    Err(Error::ProgramAborted(self.to_owned(), res.detail.stderr))
+            }
+            Capture::File(file) => {
+                if let Err(e) = file.write_all(&buf[..len]) {
+                    kill();
+                    return Err(e);
                 }
-                StatusSummary::Timeout => Err(Error::ProgramTimeout(
-                    self.to_owned(),
-                    timeout_ms,
-                    res.detail.elapsed_ms,
-                )),
             }
+            Capture::Inherit => unreachable!("inherited without a pipe"),
+        }
+        if exceeded {
+            kill();
+            if let Capture::Memory { tail: Some(tail) } = capture {
+                if kept.len() > tail {
+                    kept.drain(..kept.len() - tail);
+                }
+            }
+            return Ok(Pumped {
+                kept,
+                exceeded: true,
+            });
+        }
+    }
+    if let Capture::Memory { tail: Some(tail) } = capture {
+        if kept.len() > tail {
+            kept.drain(..kept.len() - tail);
+        }
+    }
+    Ok(Pumped {
+        kept,
+        exceeded: false,
+    })
+}
+
+/// Destination of an output stream for [`Command::exec_capture`].
+#[derive(Debug)]
+pub enum Capture {
+    /// Discards the output.
+    Null,
+    /// Keeps the output in memory as raw bytes.
+    ///
+    /// With `tail: Some(n)`, only the last `n` bytes are retained.
+    Memory { tail: Option<usize> },
+    /// Streams the output to a file without buffering it in memory.
+    File(std::fs::File),
+    /// Passes the output through to the same stream of this process.
+    ///
+    /// It is not counted against `CaptureRedirection::limit_bytes`.
+    Inherit,
+}
+impl Capture {
+    /// Splits into the `Stdio` given to the child and the capture to pump the pipe into.
+    fn into_stdio(self, limited: bool) -> (std::process::Stdio, Capture) {
+        use std::process::Stdio;
+
+        match self {
+            Capture::Null if !limited => (Stdio::null(), Capture::Null),
+            // Files can be written directly by the child as long as their size is not watched.
+            Capture::File(file) if !limited => (Stdio::from(file), Capture::Null),
+            Capture::Inherit => (Stdio::inherit(), Capture::Null),
+            capture => (Stdio::piped(), capture),
+        }
+    }
+}
+
+/// Configuration for I/O redirection with streaming capture.
+#[derive(Debug)]
+pub struct CaptureRedirection {
+    /// Standard input redirection (null, piped, inherit).
+    pub stdin: std::process::Stdio,
+    /// Destination of the standard output.
+    pub stdout: Capture,
+    /// Destination of the standard error.
+    pub stderr: Capture,
+    /// Maximum number of bytes of each of stdout and stderr.
+    ///
+    /// `None` means no limit.
+    pub limit_bytes: Option<u64>,
+}
+
+/// Result of [`Command::exec_capture`].
+#[derive(Debug, Clone)]
+pub struct Captured {
+    /// Execution status.
+    ///
+    /// `detail.stdout` and `detail.stderr` are always empty; use the fields below instead.
+    pub status: Status,
+    /// Bytes of the standard output kept by `Capture::Memory` (empty otherwise).
+    pub stdout: Vec<u8>,
+    /// Bytes of the standard error kept by `Capture::Memory` (empty otherwise).
+    pub stderr: Vec<u8>,
+}
+
+/// A spawned child process, which kills its whole process group when dropped.
+///
+/// Unlike `tokio::process::Child`, waiting for it also measures the elapsed time
+/// and the resource usage.
+#[derive(Debug)]
+pub struct Child {
+    /// Standard input, if piped.
+    pub stdin: Option<tokio::process::ChildStdin>,
+    /// Standard output, if piped.
+    pub stdout: Option<tokio::process::ChildStdout>,
+    /// Standard error, if piped.
+    pub stderr: Option<tokio::process::ChildStderr>,
+    waiter: tokio::task::JoinHandle<(std::process::ExitStatus, Usage, u64)>,
+    waited: Option<Status>,
+    group: ProcessGroup,
+}
+impl Child {
+    /// Waits for the child to exit.
+    ///
+    /// This is cancel-safe, and can be called again after the child exited.
+    ///
+    /// # Returns
+    ///
+    /// The status with the elapsed time since the spawn, the resource usage
+    /// and how the child terminated (no output is captured).
+    pub async fn wait(&mut self) -> Status {
+        if let Some(status) = &self.waited {
+            return status.to_owned();
+        }
+        let (exit_status, usage, elapsed_ms) = (&mut self.waiter).await.unwrap();
+        let status = Status {
+            summary: if exit_status.success() {
+                StatusSummary::Success
+            } else {
+                StatusSummary::Aborted
+            },
+            detail: StatusDetail {
+                stdout: "".into(),
+                stderr: "".into(),
+                elapsed_ms,
+                memory_kb: usage.memory_kb,
+                cpu_ms: usage.cpu_ms,
+                exit_code: exit_status.code(),
+                signal: sys::signal(exit_status),
+            },
+        };
+        self.waited = Some(status.to_owned());
+        status
+    }
+    /// Whether the child has exited, without blocking.
+    pub fn has_exited(&self) -> bool {
+        self.waited.is_some() || self.waiter.is_finished()
+    }
+    /// Asks the child to terminate, and waits for it to exit.
+    ///
+    /// On Unix, `SIGTERM` is sent to the child alone, so that it can stop the processes
+    /// it started by itself (see [`kill_children_on_signal`]).
+    /// The whole group is killed if the child does not exit within `grace`.
+    pub async fn terminate(&mut self, grace: std::time::Duration) -> Status {
+        sys::terminate(self.group.0.pid);
+        if tokio::time::timeout(grace, self.wait()).await.is_err() {
+            self.group.0.kill();
+        }
+        self.wait().await
+    }
+}
+
+/// Process group led by a spawned child (whose pid is the group id on Unix).
+///
+/// A child whose stdin is a terminal stays in the group of this process instead,
+/// so that it can read from the terminal, and then only the child itself is killed.
+#[derive(Debug, Clone, Copy)]
+struct Leader {
+    pid: u32,
+    leads_group: bool,
+}
+impl Leader {
+    fn kill(&self) {
+        if self.leads_group {
+            sys::kill_group(self.pid);
+        } else {
+            sys::kill_child(self.pid);
+        }
+    }
+}
+
+/// Guard of the processes started by a spawned child.
+///
+/// Every process remaining in the group is killed when the guard is dropped.
+/// Until then, the group is also killed by [`kill_children_on_signal`].
+#[derive(Debug)]
+struct ProcessGroup(Leader);
+impl ProcessGroup {
+    fn new(pid: u32) -> Self {
+        let leads_group = sys::leads_group(pid);
+        if leads_group {
+            GROUPS.lock().unwrap().insert(pid);
+        }
+        ProcessGroup(Leader { pid, leads_group })
+    }
+    fn kill(&self) {
+        self.0.kill();
+    }
+}
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        self.kill();
+        GROUPS.lock().unwrap().remove(&self.0.pid);
+    }
+}
+
+/// Process groups which are alive, i.e. whose guards are not dropped yet.
+static GROUPS: std::sync::Mutex<std::collections::BTreeSet<u32>> =
+    std::sync::Mutex::new(std::collections::BTreeSet::new());
+
+/// Kills the processes started by all the children when this process is interrupted
+/// (`SIGINT`, e.g. by Ctrl-C) or terminated (`SIGTERM`), and then exits.
+///
+/// This is needed because the children run in their own process groups,
+/// which neither the terminal nor the parent of this process signals.
+/// The handler runs on the shared [`runtime`].
+pub fn kill_children_on_signal() {
+    let _runtime = runtime().enter();
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let (Ok(mut interrupt), Ok(mut terminate)) = (
+            signal(SignalKind::interrupt()),
+            signal(SignalKind::terminate()),
+        ) else {
+            return;
+        };
+        tokio::spawn(async move {
+            let code = tokio::select! {
+                _ = interrupt.recv() => 128 + libc::SIGINT,
+                _ = terminate.recv() => 128 + libc::SIGTERM,
+            };
+            sys::kill_all_and_exit(&GROUPS.lock().unwrap(), code);
+        });
+    }
+    #[cfg(not(unix))]
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            sys::kill_all_and_exit(&GROUPS.lock().unwrap(), 130);
+        }
+    });
+}
+
+/// Resource usage of a finished child process, if the platform reports it.
+#[derive(Debug, Clone, Copy, Default)]
+struct Usage {
+    memory_kb: Option<u64>,
+    cpu_ms: Option<u64>,
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::collections::BTreeSet;
+    use std::sync::Mutex;
+
+    /// Children which are not reaped yet, whose pids therefore cannot be reused.
+    static UNREAPED: Mutex<BTreeSet<u32>> = Mutex::new(BTreeSet::new());
+
+    /// Puts the child in its own process group, unless its stdin is a terminal.
+    ///
+    /// A background group cannot read from the terminal,
+    /// so such a child stays in the (foreground) group of this process.
+    pub(super) fn set_group(command: &mut std::process::Command) {
+        use std::os::unix::process::CommandExt;
+
+        // SAFETY: `isatty` and `setpgid` are async-signal-safe.
+        unsafe {
+            command.pre_exec(|| {
+                if libc::isatty(0) == 0 && libc::setpgid(0, 0) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// Registers the spawned child, and returns whether it leads its own process group.
+    pub(super) fn leads_group(pid: u32) -> bool {
+        UNREAPED.lock().unwrap().insert(pid);
+        unsafe { libc::getpgid(pid as libc::pid_t) == pid as libc::pid_t }
+    }
+
+    /// Waits for the child and returns its exit status and resource usage.
+    pub(super) fn wait(mut child: std::process::Child) -> (std::process::ExitStatus, super::Usage) {
+        use std::os::unix::process::ExitStatusExt;
+
+        let pid = child.id() as libc::pid_t;
+        // Waits without reaping first, so that the child is not signalled after it is reaped.
+        loop {
+            let mut info = unsafe { std::mem::zeroed::<libc::siginfo_t>() };
+            let flags = libc::WEXITED | libc::WNOWAIT;
+            if unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, flags) } == 0 {
+                break;
+            }
+            if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                break;
+            }
+        }
+        let mut unreaped = UNREAPED.lock().unwrap();
+        unreaped.remove(&child.id());
+        let mut status = 0;
+        let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+        loop {
+            let ret = unsafe { libc::wait4(pid, &mut status, 0, &mut usage) };
+            if ret == pid {
+                break;
+            }
+            if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                return (child.wait().unwrap(), Default::default());
+            }
+        }
+        drop(unreaped);
+        // `ru_maxrss` is reported in bytes on macOS and in kilobytes elsewhere.
+        let maxrss = usage.ru_maxrss as u64;
+        let memory_kb = if cfg!(target_os = "macos") {
+            maxrss / 1024
         } else {
-            Ok(res)
+            maxrss
+        };
+        let millis = |time: libc::timeval| time.tv_sec as u64 * 1000 + time.tv_usec as u64 / 1000;
+        let usage = super::Usage {
+            memory_kb: Some(memory_kb),
+            cpu_ms: Some(millis(usage.ru_utime) + millis(usage.ru_stime)),
+        };
+        (std::process::ExitStatus::from_raw(status), usage)
+    }
+
+    pub(super) fn kill_group(pgid: u32) {
+        unsafe {
+            libc::kill(-(pgid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+
+    /// Kills the child alone, if it is not reaped yet.
+    pub(super) fn kill_child(pid: u32) {
+        signal_child(pid, libc::SIGKILL);
+    }
+
+    /// Sends `SIGTERM` to the child alone, if it is not reaped yet.
+    pub(super) fn terminate(pid: u32) {
+        signal_child(pid, libc::SIGTERM);
+    }
+
+    fn signal_child(pid: u32, signal: i32) {
+        let unreaped = UNREAPED.lock().unwrap();
+        if unreaped.contains(&pid) {
+            unsafe {
+                libc::kill(pid as libc::pid_t, signal);
+            }
+        }
+    }
+
+    /// Terminates the `groups` and the children outside of them,
+    /// kills them if they are still alive after a moment, and exits with `code`.
+    ///
+    /// The children are never reaped meanwhile, so no one observes their deaths.
+    pub(super) fn kill_all_and_exit(groups: &BTreeSet<u32>, code: i32) -> ! {
+        let unreaped = UNREAPED.lock().unwrap();
+        for (i, signal) in [libc::SIGTERM, libc::SIGKILL].into_iter().enumerate() {
+            if i > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            for &pgid in groups {
+                unsafe { libc::kill(-(pgid as libc::pid_t), signal) };
+            }
+            for &pid in unreaped.difference(groups) {
+                unsafe { libc::kill(pid as libc::pid_t, signal) };
+            }
+        }
+        std::process::exit(code);
+    }
+
+    pub(super) fn signal(status: std::process::ExitStatus) -> Option<i32> {
+        use std::os::unix::process::ExitStatusExt;
+
+        status.signal()
+    }
+
+    pub(super) const SIGNAL_NAMES: &[(i32, &str)] = &[
+        (libc::SIGHUP, "SIGHUP"),
+        (libc::SIGINT, "SIGINT"),
+        (libc::SIGQUIT, "SIGQUIT"),
+        (libc::SIGILL, "SIGILL"),
+        (libc::SIGTRAP, "SIGTRAP"),
+        (libc::SIGABRT, "SIGABRT"),
+        (libc::SIGBUS, "SIGBUS"),
+        (libc::SIGFPE, "SIGFPE"),
+        (libc::SIGKILL, "SIGKILL"),
+        (libc::SIGSEGV, "SIGSEGV"),
+        (libc::SIGPIPE, "SIGPIPE"),
+        (libc::SIGALRM, "SIGALRM"),
+        (libc::SIGTERM, "SIGTERM"),
+        (libc::SIGXCPU, "SIGXCPU"),
+        (libc::SIGXFSZ, "SIGXFSZ"),
+    ];
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use std::collections::BTreeSet;
+    use std::sync::Mutex;
+
+    /// Children being waited for, which are the only ones `kill_group` has to kill.
+    static RUNNING: Mutex<BTreeSet<u32>> = Mutex::new(BTreeSet::new());
+
+    /// Waits for the child and returns its exit status (resource usage is not available).
+    pub(super) fn wait(mut child: std::process::Child) -> (std::process::ExitStatus, super::Usage) {
+        let pid = child.id();
+        RUNNING.lock().unwrap().insert(pid);
+        let status = child.wait().unwrap();
+        RUNNING.lock().unwrap().remove(&pid);
+        (status, Default::default())
+    }
+
+    /// Process groups are not available, so the process tree is killed by `taskkill`.
+    ///
+    /// The blocked `wait` returns once the child is killed.
+    pub(super) fn kill_group(pid: u32) {
+        if !RUNNING.lock().unwrap().contains(&pid) {
+            return;
         }
+        let _ = std::process::Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+    }
+
+    /// Process groups are not available, so every child leads its own process tree.
+    pub(super) fn set_group(_command: &mut std::process::Command) {}
+
+    pub(super) fn leads_group(_pid: u32) -> bool {
+        true
     }
+
+    pub(super) fn kill_child(pid: u32) {
+        kill_group(pid);
+    }
+
+    /// There is no graceful termination, so the process tree is killed at once.
+    pub(super) fn terminate(pid: u32) {
+        kill_group(pid);
+    }
+
+    pub(super) fn kill_all_and_exit(groups: &BTreeSet<u32>, code: i32) -> ! {
+        groups.iter().for_each(|&pid| kill_group(pid));
+        std::process::exit(code);
+    }
+
+    pub(super) fn signal(_status: std::process::ExitStatus) -> Option<i32> {
+        None
+    }
+
+    pub(super) const SIGNAL_NAMES: &[(i32, &str)] = &[];
 }
 
 impl<T: AsRef<str>> From<T> for Command {
@@ -411,6 +1245,120 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_command_exec_measures_elapsed_time() {
+        let cmd = Command::new("sleep", vec!["0.2"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let status = cmd.exec(redirect, 5000, false).unwrap();
+        assert_eq!(status.summary, StatusSummary::Success);
+        assert!(status.detail.elapsed_ms >= 200);
+        assert_eq!(status.detail.exit_code, Some(0));
+        assert_eq!(status.detail.signal, None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_command_exec_reports_memory() {
+        let cmd = Command::new("echo", vec!["hello"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let status = cmd.exec(redirect, 5000, false).unwrap();
+        assert!(status.detail.memory_kb.is_some_and(|kb| kb > 0));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_command_exec_reports_cpu_time() {
+        let cmd = Command::new(
+            "sh",
+            vec!["-c", "i=0; while [ $i -lt 100000 ]; do i=$((i+1)); done"],
+        );
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let status = cmd.exec(redirect, 10000, false).unwrap();
+        let cpu_ms = status.detail.cpu_ms.unwrap();
+        assert!(cpu_ms > 0);
+        assert!(cpu_ms <= status.detail.elapsed_ms + 10);
+    }
+
+    #[test]
+    fn test_command_exec_over_timelimit_is_timeout() {
+        let cmd = Command::new("sleep", vec!["0.3"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        // Finishes before the hard limit (2x), but still exceeds the time limit.
+        let status = cmd.exec(redirect, 200, false).unwrap();
+        assert_eq!(status.summary, StatusSummary::Timeout);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_status_from_signal() {
+        let output = std::process::Output {
+            status: std::process::ExitStatus::from_raw(libc::SIGSEGV),
+            stdout: b"".to_vec(),
+            stderr: b"".to_vec(),
+        };
+        let status = Status::from(output);
+        assert_eq!(status.summary, StatusSummary::Aborted);
+        assert_eq!(status.detail.exit_code, None);
+        assert_eq!(status.detail.signal, Some(libc::SIGSEGV));
+        assert_eq!(status.detail.termination().unwrap().to_string(), "SIGSEGV");
+    }
+
+    #[test]
+    fn test_command_exec_reports_exit_code() {
+        let cmd = Command::new("sh", vec!["-c", "exit 3"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let status = cmd.exec(redirect, 5000, false).unwrap();
+        assert_eq!(status.summary, StatusSummary::Aborted);
+        assert_eq!(status.detail.exit_code, Some(3));
+        assert_eq!(status.detail.termination(), Some(Termination::Exit(3)));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_command_exec_reports_signal() {
+        let cmd = Command::new("sh", vec!["-c", "kill -SEGV $$"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let status = cmd.exec(redirect, 5000, false).unwrap();
+        assert_eq!(status.summary, StatusSummary::Aborted);
+        assert_eq!(status.detail.signal, Some(libc::SIGSEGV));
+        assert_eq!(status.detail.termination().unwrap().to_string(), "SIGSEGV");
+    }
+
+    #[test]
+    fn test_signal_name_unknown() {
+        assert_eq!(signal_name(1000), "SIG1000");
+    }
+
     #[test]
     fn test_command_exec_nonexistent_program() {
         let cmd = Command::new("nonexistent_program_xyz", Vec::<String>::new());
@@ -492,6 +1440,233 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_command_exec_kills_grandchildren() {
+        // The background `sleep` inherits stdout, so the pipe stays open until it is killed.
+        let cmd = Command::new("sh", vec!["-c", "sleep 5 & echo started"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let start = std::time::Instant::now();
+        let status = cmd.exec(redirect, 5000, false).unwrap();
+        assert_eq!(status.summary, StatusSummary::Success);
+        assert!(status.detail.stdout.contains("started"));
+        assert!(start.elapsed() < std::time::Duration::from_secs(3));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_command_exec_timeout_kills_grandchildren() {
+        let cmd = Command::new("sh", vec!["-c", "sleep 5; echo done"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let start = std::time::Instant::now();
+        let status = cmd.exec(redirect, 100, false).unwrap();
+        assert_eq!(status.summary, StatusSummary::Timeout);
+        assert!(start.elapsed() < std::time::Duration::from_secs(3));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_command_exec_timeout_detaches_escaped_readers() {
+        // `setsid` leaves the process group, so the pipe is held open after the kill.
+        let cmd = Command::new("sh", vec!["-c", "setsid sleep 5 & sleep 5"]);
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::piped(),
+        };
+
+        let start = std::time::Instant::now();
+        let status = cmd.exec(redirect, 100, false).unwrap();
+        assert_eq!(status.summary, StatusSummary::Timeout);
+        assert!(start.elapsed() < std::time::Duration::from_secs(3));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_child_drop_kills_group() {
+        let redirect = IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::piped(),
+            stderr: Stdio::null(),
+        };
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            use tokio::io::AsyncReadExt;
+
+            let mut child = Command::new("sh", vec!["-c", "sleep 5 & wait"])
+                .spawn(redirect)
+                .unwrap();
+            let mut stdout = child.stdout.take().unwrap();
+            drop(child);
+            // EOF arrives only once every process holding the pipe is gone.
+            let mut buf = Vec::new();
+            let read = tokio::time::timeout(
+                tokio::time::Duration::from_secs(3),
+                stdout.read_to_end(&mut buf),
+            )
+            .await;
+            assert!(read.is_ok());
+        });
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_child_terminate() {
+        let redirect = || IoRedirection {
+            stdin: Stdio::null(),
+            stdout: Stdio::null(),
+            stderr: Stdio::null(),
+        };
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let grace = std::time::Duration::from_secs(1);
+            // Handles `SIGTERM` by itself.
+            let mut child = Command::new("sh", vec!["-c", "trap 'exit 7' TERM; sleep 5 & wait"])
+                .spawn(redirect())
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            let status = child.terminate(grace).await;
+            assert_eq!(status.detail.exit_code, Some(7));
+
+            // Ignores `SIGTERM`, so it is killed after the grace period.
+            let mut child = Command::new("sh", vec!["-c", "trap '' TERM; sleep 5"])
+                .spawn(redirect())
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            let status = child.terminate(grace).await;
+            assert_eq!(status.detail.signal, Some(libc::SIGKILL));
+        });
+    }
+
+    #[test]
+    fn test_runtime_is_shared() {
+        assert!(std::ptr::eq(runtime(), runtime()));
+    }
+
+    #[test]
+    fn test_command_exec_async_concurrently() {
+        let statuses = runtime().block_on(async {
+            let cmd = Command::new("sleep", vec!["0.2"]);
+            let exec = || {
+                cmd.exec_async(
+                    IoRedirection {
+                        stdin: Stdio::null(),
+                        stdout: Stdio::piped(),
+                        stderr: Stdio::piped(),
+                    },
+                    5000,
+                    true,
+                )
+            };
+            tokio::join!(exec(), exec())
+        });
+        assert!(statuses.0.is_ok());
+        assert!(statuses.1.is_ok());
+    }
+
+    #[test]
+    fn test_command_exec_capture_byte_exact() {
+        let cmd = Command::new("printf", vec!["\\377\\000a"]);
+        let redirect = CaptureRedirection {
+            stdin: Stdio::null(),
+            stdout: Capture::Memory { tail: None },
+            stderr: Capture::Memory { tail: None },
+            limit_bytes: None,
+        };
+
+        let captured = cmd.exec_capture(redirect, 5000, true).unwrap();
+        assert_eq!(captured.status.summary, StatusSummary::Success);
+        assert_eq!(captured.stdout, vec![0xff, 0x00, b'a']);
+        assert!(captured.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_command_exec_capture_output_limit() {
+        let cmd = Command::new("yes", Vec::<String>::new());
+        let redirect = CaptureRedirection {
+            stdin: Stdio::null(),
+            stdout: Capture::Memory { tail: None },
+            stderr: Capture::Null,
+            limit_bytes: Some(100_000),
+        };
+
+        let captured = cmd.exec_capture(redirect, 5000, false).unwrap();
+        assert_eq!(captured.status.summary, StatusSummary::OutputLimitExceeded);
+        assert_eq!(captured.stdout.len(), 100_000);
+    }
+
+    #[test]
+    fn test_command_exec_capture_output_limit_with_ensure_success() {
+        let cmd = Command::new("yes", Vec::<String>::new());
+        let redirect = CaptureRedirection {
+            stdin: Stdio::null(),
+            stdout: Capture::Null,
+            stderr: Capture::Null,
+            limit_bytes: Some(100),
+        };
+
+        match cmd.exec_capture(redirect, 5000, true).unwrap_err() {
+            Error::ProgramOutputLimitExceeded(_, 100) => {}
+            _ => panic!("Expected ProgramOutputLimitExceeded error"),
+        }
+    }
+
+    #[test]
+    fn test_command_exec_capture_stderr_tail() {
+        let cmd = Command::new("sh", vec!["-c", "seq 100000 >&2"]);
+        let redirect = CaptureRedirection {
+            stdin: Stdio::null(),
+            stdout: Capture::Null,
+            stderr: Capture::Memory { tail: Some(7) },
+            limit_bytes: None,
+        };
+
+        let captured = cmd.exec_capture(redirect, 5000, true).unwrap();
+        assert_eq!(captured.stderr, b"100000\n");
+    }
+
+    #[test]
+    fn test_command_exec_capture_to_file() {
+        for limit_bytes in [None, Some(1 << 20)] {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let cmd = Command::new("seq", vec!["3"]);
+            let redirect = CaptureRedirection {
+                stdin: Stdio::null(),
+                stdout: Capture::File(file.reopen().unwrap()),
+                stderr: Capture::Null,
+                limit_bytes,
+            };
+
+            let captured = cmd.exec_capture(redirect, 5000, true).unwrap();
+            assert!(captured.stdout.is_empty());
+            assert_eq!(std::fs::read(file.path()).unwrap(), b"1\n2\n3\n");
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_command_exec_capture_failed() {
+        // Only a limited capture is pumped by us; otherwise the child writes the file itself.
+        let cmd = Command::new("seq", vec!["3"]);
+        let redirect = CaptureRedirection {
+            stdin: Stdio::null(),
+            stdout: Capture::File(std::fs::File::create("/dev/full").unwrap()),
+            stderr: Capture::Null,
+            limit_bytes: Some(1 << 20),
+        };
+
+        let result = cmd.exec_capture(redirect, 5000, true);
+        assert!(matches!(result, Err(Error::CaptureFailed(..))));
+    }
+
     #[test]
     fn test_status_summary_equality() {
         assert_eq!(StatusSummary::Success, StatusSummary::Success);
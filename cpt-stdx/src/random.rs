@@ -0,0 +1,445 @@
+use std::collections::BTreeSet;
+
+/// Bucket count of `std::unordered_map` in libstdc++ around 10^5 elements.
+///
+/// Keys which are multiples of it all fall into the same bucket.
+pub const ANTI_HASH_PRIME: i64 = 107897;
+
+/// Distribution of the values of [`Rng::array`], oriented to edge cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Distribution {
+    /// Independent uniform values.
+    #[default]
+    Uniform,
+    /// A single uniform value repeated.
+    AllEqual,
+    /// Uniform values in non-decreasing order.
+    Sorted,
+    /// Multiples of [`ANTI_HASH_PRIME`], or uniform values if the range has none.
+    AntiHash,
+    /// The maximum value repeated.
+    MaxValue,
+}
+
+/// Shape of the trees of [`Rng::tree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TreeShape {
+    /// Each vertex is attached to a uniformly chosen earlier one, which makes shallow trees.
+    #[default]
+    Recursive,
+    /// Uniform over all labeled trees, decoded from a random Prüfer sequence.
+    Uniform,
+    /// A single path, the deepest tree.
+    Path,
+    /// A center adjacent to all the other vertices.
+    Star,
+}
+
+/// Seeded pseudo-random generator, reproducible across builds and platforms.
+///
+/// xorshift64* seeded by splitmix64.
+///
+/// # Example
+///
+/// ```rust
+/// use cpt_stdx::random::Rng;
+///
+/// let mut rng = Rng::new(1);
+/// let value = rng.range(1, 6);
+/// assert!((1..=6).contains(&value));
+/// assert_eq!(Rng::new(1).range(1, 6), value);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Generator seeded by `seed`.
+    ///
+    /// The same seed always yields the same sequence, on any build and platform, so a case
+    /// can be regenerated from its seed alone. Every seed is valid, including `0`.
+    pub fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        Self {
+            state: (z ^ (z >> 31)).max(1),
+        }
+    }
+
+    /// Next uniform 64-bit value, which every other method is built on.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    /// Uniform integer in `[min, max]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min > max`.
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        assert!(min <= max, "empty range [{}, {}]", min, max);
+        let width = max.abs_diff(min).wrapping_add(1);
+        let offset = if width == 0 {
+            self.next_u64()
+        } else {
+            self.next_u64() % width
+        };
+        min.wrapping_add(offset as i64)
+    }
+
+    /// Shuffles `items` uniformly (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i as i64) as usize);
+        }
+    }
+
+    /// A uniformly chosen element, or `None` if `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        (!items.is_empty()).then(|| &items[self.range(0, items.len() as i64 - 1) as usize])
+    }
+
+    /// `len` values in `[min, max]` following `distribution`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min > max`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cpt_stdx::random::{Distribution, Rng};
+    ///
+    /// let mut rng = Rng::new(1);
+    /// assert_eq!(rng.array(3, 1, 10, Distribution::MaxValue), [10, 10, 10]);
+    /// ```
+    pub fn array(
+        &mut self,
+        len: usize,
+        min: i64,
+        max: i64,
+        distribution: Distribution,
+    ) -> Vec<i64> {
+        match distribution {
+            Distribution::Uniform => (0..len).map(|_| self.range(min, max)).collect(),
+            Distribution::AllEqual => vec![self.range(min, max); len],
+            Distribution::Sorted => {
+                let mut values = self.array(len, min, max, Distribution::Uniform);
+                values.sort();
+                values
+            }
+            Distribution::AntiHash => {
+                let (first, last) = (
+                    min.div_euclid(ANTI_HASH_PRIME) + (min.rem_euclid(ANTI_HASH_PRIME) != 0) as i64,
+                    max.div_euclid(ANTI_HASH_PRIME),
+                );
+                if first > last {
+                    return self.array(len, min, max, Distribution::Uniform);
+                }
+                (0..len)
+                    .map(|_| self.range(first, last) * ANTI_HASH_PRIME)
+                    .collect()
+            }
+            Distribution::MaxValue => {
+                assert!(min <= max, "empty range [{}, {}]", min, max);
+                vec![max; len]
+            }
+        }
+    }
+
+    /// `len` distinct values in `[min, max]` in random order,
+    /// or `None` if the range has fewer than `len` values.
+    pub fn distinct(&mut self, len: usize, min: i64, max: i64) -> Option<Vec<i64>> {
+        if min > max || (max.abs_diff(min) as u128) + 1 < len as u128 {
+            return None;
+        }
+        if (max.abs_diff(min) as u128) < 2 * len as u128 {
+            // Dense: a prefix of a shuffled range.
+            let mut values = (min..=max).collect::<Vec<_>>();
+            self.shuffle(&mut values);
+            values.truncate(len);
+            return Some(values);
+        }
+        let mut seen = BTreeSet::new();
+        let mut values = Vec::with_capacity(len);
+        while values.len() < len {
+            let value = self.range(min, max);
+            if seen.insert(value) {
+                values.push(value);
+            }
+        }
+        Some(values)
+    }
+
+    /// A string of length `len` over `alphabet`, or `None` if `alphabet` is empty.
+    pub fn string(&mut self, len: usize, alphabet: &[char]) -> Option<String> {
+        (0..len).map(|_| self.choose(alphabet).copied()).collect()
+    }
+
+    /// A permutation of `1..=n`.
+    pub fn permutation(&mut self, n: usize) -> Vec<usize> {
+        let mut values = (1..=n).collect::<Vec<_>>();
+        self.shuffle(&mut values);
+        values
+    }
+
+    /// `n - 1` edges of a random tree of `shape` on vertices `1..=n`, in random order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cpt_stdx::random::{Rng, TreeShape};
+    ///
+    /// let mut rng = Rng::new(1);
+    /// assert_eq!(rng.tree(5, TreeShape::Uniform).len(), 4);
+    /// ```
+    pub fn tree(&mut self, n: usize, shape: TreeShape) -> Vec<(usize, usize)> {
+        let labels = self.permutation(n);
+        let mut edges = match shape {
+            TreeShape::Recursive => (1..n)
+                .map(|v| (self.range(0, v as i64 - 1) as usize, v))
+                .collect::<Vec<_>>(),
+            TreeShape::Uniform => self.pruefer_tree(n),
+            TreeShape::Path => (1..n).map(|v| (v - 1, v)).collect(),
+            TreeShape::Star => (1..n).map(|v| (0, v)).collect(),
+        };
+        edges.sort();
+        let mut edges = edges
+            .into_iter()
+            .map(|(u, v)| (labels[u], labels[v]))
+            .collect::<Vec<_>>();
+        self.shuffle(&mut edges);
+        edges
+    }
+
+    /// Edges of the tree on `0..n` decoded from a uniform Prüfer sequence.
+    fn pruefer_tree(&mut self, n: usize) -> Vec<(usize, usize)> {
+        if n < 2 {
+            return vec![];
+        }
+        let sequence = (0..n - 2)
+            .map(|_| self.range(0, n as i64 - 1) as usize)
+            .collect::<Vec<_>>();
+        let mut degree = vec![1; n];
+        for &v in &sequence {
+            degree[v] += 1;
+        }
+        let mut leaves = (0..n).filter(|&v| degree[v] == 1).collect::<BTreeSet<_>>();
+        let mut edges = Vec::with_capacity(n - 1);
+        for v in sequence {
+            let leaf = leaves.pop_first().unwrap();
+            edges.push((leaf, v));
+            degree[v] -= 1;
+            if degree[v] == 1 {
+                leaves.insert(v);
+            }
+        }
+        edges.push((leaves.pop_first().unwrap(), leaves.pop_first().unwrap()));
+        edges
+    }
+
+    /// `m` edges of a random simple graph on vertices `1..=n`, in random order,
+    /// which is connected if `connected`.
+    ///
+    /// Returns `None` if no such graph exists.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cpt_stdx::random::Rng;
+    ///
+    /// let mut rng = Rng::new(1);
+    /// assert_eq!(rng.graph(4, 6, true).unwrap().len(), 6);
+    /// assert_eq!(rng.graph(4, 7, false), None);
+    /// assert_eq!(rng.graph(4, 2, true), None);
+    /// ```
+    pub fn graph(&mut self, n: usize, m: usize, connected: bool) -> Option<Vec<(usize, usize)>> {
+        let max_edges = n as u128 * (n as u128).saturating_sub(1) / 2;
+        if (m as u128) > max_edges || (connected && n > 0 && m < n - 1) {
+            return None;
+        }
+        let labels = self.permutation(n);
+        let mut edges = BTreeSet::new();
+        if connected {
+            for v in 1..n {
+                let u = self.range(0, v as i64 - 1) as usize;
+                edges.insert((u, v));
+            }
+        }
+        while edges.len() < m {
+            let u = self.range(0, n as i64 - 1) as usize;
+            let v = self.range(0, n as i64 - 1) as usize;
+            if u != v {
+                edges.insert((u.min(v), u.max(v)));
+            }
+        }
+        let mut edges = edges
+            .into_iter()
+            .map(|(u, v)| (labels[u], labels[v]))
+            .collect::<Vec<_>>();
+        self.shuffle(&mut edges);
+        Some(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Distribution, Rng, TreeShape, ANTI_HASH_PRIME};
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeSet;
+    use test_case::test_case;
+
+    fn is_connected(n: usize, edges: &[(usize, usize)]) -> bool {
+        let mut parent = (0..=n).collect::<Vec<_>>();
+        fn root(parent: &mut [usize], v: usize) -> usize {
+            if parent[v] != v {
+                parent[v] = root(parent, parent[v]);
+            }
+            parent[v]
+        }
+        for &(u, v) in edges {
+            let (u, v) = (root(&mut parent, u), root(&mut parent, v));
+            parent[u] = v;
+        }
+        (1..=n)
+            .map(|v| root(&mut parent, v))
+            .collect::<BTreeSet<_>>()
+            .len()
+            <= 1
+    }
+
+    #[test]
+    fn reproducible() {
+        let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+        assert_eq!(
+            a.array(100, 0, 1 << 40, Distribution::Uniform),
+            b.array(100, 0, 1 << 40, Distribution::Uniform)
+        );
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn range_bounds() {
+        let mut rng = Rng::new(1);
+        assert!((0..1000)
+            .map(|_| rng.range(-3, 3))
+            .all(|v| (-3..=3).contains(&v)));
+        assert_eq!(rng.range(5, 5), 5);
+        rng.range(i64::MIN, i64::MAX);
+    }
+
+    #[test_case(Distribution::AllEqual; "all equal")]
+    #[test_case(Distribution::MaxValue; "max value")]
+    fn equal_values(distribution: Distribution) {
+        let values = Rng::new(1).array(10, 1, 100, distribution);
+        assert!(values.iter().all(|&v| v == values[0]));
+    }
+
+    #[test]
+    fn sorted_values() {
+        let values = Rng::new(1).array(100, 1, 100, Distribution::Sorted);
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn anti_hash_values() {
+        let values = Rng::new(1).array(100, 1, 1_000_000_000, Distribution::AntiHash);
+        assert!(values
+            .iter()
+            .all(|v| v % ANTI_HASH_PRIME == 0 && (1..=1_000_000_000).contains(v)));
+        // Falls back to uniform values without any multiple in the range.
+        let values = Rng::new(1).array(10, 1, 10, Distribution::AntiHash);
+        assert!(values.iter().all(|v| (1..=10).contains(v)));
+    }
+
+    #[test_case(5, 1, 5; "dense")]
+    #[test_case(100, 1, 1_000_000; "sparse")]
+    fn distinct_values(len: usize, min: i64, max: i64) {
+        let values = Rng::new(1).distinct(len, min, max).unwrap();
+        assert_eq!(values.len(), len);
+        assert_eq!(values.iter().collect::<BTreeSet<_>>().len(), len);
+        assert!(values.iter().all(|v| (min..=max).contains(v)));
+        assert_eq!(Rng::new(1).distinct(6, 1, 5), None);
+    }
+
+    #[test]
+    fn string_over_alphabet() {
+        let mut rng = Rng::new(1);
+        let string = rng.string(100, &['a', 'b']).unwrap();
+        assert_eq!(string.len(), 100);
+        assert!(string.chars().all(|c| c == 'a' || c == 'b'));
+        assert_eq!(rng.string(1, &[]), None);
+    }
+
+    #[test]
+    fn permutation_of_range() {
+        let mut values = Rng::new(1).permutation(10);
+        values.sort();
+        assert_eq!(values, (1..=10).collect::<Vec<_>>());
+    }
+
+    #[test_case(1, TreeShape::Recursive; "single vertex")]
+    #[test_case(2, TreeShape::Uniform; "single edge")]
+    #[test_case(100, TreeShape::Recursive; "recursive")]
+    #[test_case(100, TreeShape::Uniform; "uniform")]
+    #[test_case(100, TreeShape::Path; "path")]
+    #[test_case(100, TreeShape::Star; "star")]
+    fn tree_is_connected(n: usize, shape: TreeShape) {
+        let edges = Rng::new(1).tree(n, shape);
+        assert_eq!(edges.len(), n - 1);
+        assert!(is_connected(n, &edges));
+    }
+
+    #[test_case(TreeShape::Path, 2; "path")]
+    #[test_case(TreeShape::Star, 99; "star")]
+    fn tree_shape_degree(shape: TreeShape, max_degree: usize) {
+        let mut degree = [0; 101];
+        for (u, v) in Rng::new(1).tree(100, shape) {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+        assert_eq!(degree.iter().max(), Some(&max_degree));
+    }
+
+    #[test]
+    fn uniform_tree_covers_all_trees() {
+        // Cayley's formula: 4^2 = 16 labeled trees on 4 vertices.
+        let mut rng = Rng::new(1);
+        let trees = (0..1000)
+            .map(|_| {
+                let mut edges = rng
+                    .tree(4, TreeShape::Uniform)
+                    .into_iter()
+                    .map(|(u, v)| (u.min(v), u.max(v)))
+                    .collect::<Vec<_>>();
+                edges.sort();
+                edges
+            })
+            .collect::<BTreeSet<_>>();
+        assert_eq!(trees.len(), 16);
+    }
+
+    #[test_case(10, 9, true; "tree")]
+    #[test_case(10, 45, true; "complete")]
+    #[test_case(10, 5, false; "sparse")]
+    fn graph_is_simple(n: usize, m: usize, connected: bool) {
+        let edges = Rng::new(1).graph(n, m, connected).unwrap();
+        assert_eq!(edges.len(), m);
+        assert!(edges
+            .iter()
+            .all(|&(u, v)| u != v && (1..=n).contains(&u) && (1..=n).contains(&v)));
+        let unique = edges
+            .iter()
+            .map(|&(u, v)| (u.min(v), u.max(v)))
+            .collect::<BTreeSet<_>>();
+        assert_eq!(unique.len(), m);
+        if connected {
+            assert!(is_connected(n, &edges));
+        }
+    }
+}
@@ -1,3 +1,97 @@
+/// Prefix of the temporary directories, followed by `<pid>-`.
+const PREFIX: &str = "cpt-";
+
+/// Age after which a temporary directory of a process no longer running is removed.
+///
+/// The pid may be of another pid namespace (e.g. a container sharing the temporary directory),
+/// so a directory in use must have been left untouched for this long to be removed.
+const STALE_AGE: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Creates a private temporary directory named `cpt-<pid>-*`.
+///
+/// The directory and its contents are removed when the returned guard is dropped.
+/// As this does not happen when the process is interrupted (e.g. by Ctrl+C),
+/// such directories are removed later by [`remove_stale`].
+///
+/// # Example
+///
+/// ```rust
+/// use cpt_stdx::tempfile::tempdir;
+///
+/// let tempdir = tempdir().expect("Failed to create tempdir");
+/// let path = tempdir.path().to_path_buf();
+/// assert!(path.is_dir());
+/// drop(tempdir);
+/// assert!(!path.exists());
+/// ```
+pub fn tempdir() -> std::io::Result<tempfile::TempDir> {
+    tempfile::Builder::new()
+        .prefix(&format!("{}{}-", PREFIX, std::process::id()))
+        .tempdir()
+}
+
+/// Removes the temporary directories left by the processes of this user no longer running,
+/// which have not been modified for a day (Unix only).
+///
+/// This scans the whole temporary directory, so it should be called once per session
+/// (e.g. of `cpt hack`) rather than for every [`tempdir`].
+pub fn remove_stale() {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let pid = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix(PREFIX))
+            .and_then(|rest| rest.split_once('-'))
+            .and_then(|(pid, _)| pid.parse::<u32>().ok());
+        let Some(pid) = pid else {
+            continue;
+        };
+        let is_old = |metadata: &std::fs::Metadata| {
+            metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= STALE_AGE)
+        };
+        let is_stale = entry.metadata().is_ok_and(|metadata| {
+            metadata.is_dir() && is_own(&metadata) && is_old(&metadata) && !is_running(pid)
+        });
+        if is_stale {
+            // It may be removed concurrently.
+            let _ = std::fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_own(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.uid() == unsafe { libc::geteuid() }
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    // Signal 0 only checks the existence, and `EPERM` means a process of another user.
+    let ret = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// The owner is not checked, so nothing is considered removable.
+#[cfg(not(unix))]
+fn is_own(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// The liveness of other processes is not checked, so nothing is considered stale.
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
 /// Runs a function with a temporary directory and automatically cleans it up.
 ///
 /// Creates a temporary directory with the prefix "cpt-", executes the provided
@@ -33,7 +127,7 @@ pub fn with_tempdir<F, R>(func: F) -> R
 where
     F: FnOnce(&tempfile::TempDir) -> R,
 {
-    let tempdir = tempfile::Builder::new().prefix("cpt-").tempdir().unwrap();
+    let tempdir = tempdir().unwrap();
     let result = func(&tempdir);
     tempdir.close().unwrap();
     result
@@ -89,6 +183,53 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_tempdir_removed_on_drop() {
+        let tempdir = tempdir().unwrap();
+        let path = tempdir.path().to_path_buf();
+        fs::write(path.join("test.txt"), "hello").unwrap();
+        assert!(path
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("cpt-"));
+
+        drop(tempdir);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_remove_stale() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        let old = std::time::SystemTime::now() - STALE_AGE * 2;
+        let make = |name: String, modified: Option<std::time::SystemTime>| {
+            let path = std::env::temp_dir().join(name);
+            fs::create_dir_all(path.join("nested")).unwrap();
+            if let Some(modified) = modified {
+                let times = fs::FileTimes::new().set_modified(modified);
+                fs::File::open(&path).unwrap().set_times(times).unwrap();
+            }
+            path
+        };
+        let stale = make(format!("{}{}-stale", PREFIX, pid), Some(old));
+        let recent = make(format!("{}{}-recent", PREFIX, pid), None);
+        let running = make(
+            format!("{}{}-running", PREFIX, std::process::id()),
+            Some(old),
+        );
+
+        remove_stale();
+        assert!(!stale.exists());
+        assert!(recent.exists());
+        assert!(running.exists());
+        fs::remove_dir_all(recent).unwrap();
+        fs::remove_dir_all(running).unwrap();
+    }
+
     #[test]
     fn test_with_tempdir_multiple_calls() {
         let path1 = with_tempdir(|tempdir| tempdir.path().to_path_buf());